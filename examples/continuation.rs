@@ -0,0 +1,55 @@
+use std::env;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use hks_method::continuation::ContinuationParameters;
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, Integrator};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let index = args.get(1).map_or(6, |i| i.parse::<usize>().unwrap());
+
+    let params = IntegrationParameters::default();
+
+    // The benchmark point with the coupling at index replaced by s
+    let mut origin = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+    origin.couplings[index] = 0.;
+    let mut direction = [0.; 7];
+    direction[index] = 1.;
+    let continuation = ContinuationParameters {
+        origin: origin.clone(),
+        direction,
+        range: (-0.5, 0.5),
+        initial_step: 0.01,
+        min_step: 1E-4,
+        max_step: 0.05,
+        max_scale_change: 0.25,
+        refinement_tolerance: 1E-6,
+    };
+
+    let mut integrator = Integrator::new(params, Box::new(MainModel), origin);
+    let curve = integrator.continue_breaking_scale(&continuation).expect("Invalid continuation parameters");
+    println!("{} points", curve.points.len());
+    for discontinuity in &curve.discontinuities {
+        println!(
+            "{} -> {} between {} and {}",
+            discontinuity.below.name(),
+            discontinuity.above.name(),
+            discontinuity.lower,
+            discontinuity.upper
+        );
+    }
+
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+    curve.save_to_csv(&format!("out/continuation_{}.csv", index)).unwrap();
+    let (image, min, max) = curve.plot::<600, 400>();
+    image.save_to_png(&format!("out/continuation_{}.png", index)).unwrap();
+    let file = File::create(format!("out/continuation_{}.txt", index)).unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    println!("Done!");
+}
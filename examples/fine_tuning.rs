@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, thread};
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::fine_tuning_consumer::FineTuningFilter;
+use hks_method::scanner::consumer::plane_gradient_consumer::{allowed_fine_tuning, PlaneGradientConsumer};
+use hks_method::scanner::consumer::record_consumer::EventRecordConsumer;
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::simulation::IntegrationParameters;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let point = [0.3, -0.3, 0.1, 0.0, 0.0, 0.0];
+    let index_x = 5usize;
+    let index_y = 6usize;
+    let threshold = 10.;
+
+    let mut coupling_ranges = [(0.0, 0.0); 7];
+    coupling_ranges[0] = (0.425, 0.425);
+    for i in 1..7 {
+        if i == index_x || i == index_y {
+            coupling_ranges[i] = (-0.5, 0.5);
+        } else {
+            coupling_ranges[i] = (point[i - 1], point[i - 1]);
+        }
+    }
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let params = IntegrationParameters {
+        sensitivity: true,
+        ..Default::default()
+    };
+
+    // Map of the fine-tuning over the plane
+    let consumer: PlaneGradientConsumer<7, 400, 400> =
+        PlaneGradientConsumer::new(coupling_ranges, index_x, index_y, allowed_fine_tuning);
+    let mut scanner = MultiThreadedScanner::new(coupling_ranges, params.clone(), MainModel, consumer);
+    scanner.scan(num_threads, num_samples);
+    let (image, min, max) = scanner.consumer.render();
+
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+    image.save_to_png(&format!("out/fine_tuning_{}_{}.png", index_x, index_y)).unwrap();
+    let file = File::create(format!("out/fine_tuning_{}_{}.txt", index_x, index_y)).unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    // Allowed breakings with little fine-tuning
+    let filter = FineTuningFilter::new(EventRecordConsumer::new(), threshold);
+    let mut scanner = MultiThreadedScanner::new(coupling_ranges, params, MainModel, filter);
+    scanner.scan(num_threads, num_samples);
+    println!("Rejected {} allowed breakings with fine-tuning above {}", scanner.consumer.rejected, threshold);
+    scanner.consumer.consumer.save_to_csv("out/fine_tuning_events.csv").unwrap();
+
+    println!("Done!");
+}
@@ -0,0 +1,32 @@
+use hks_method::fixed_point::{find_fixed_points, FixedPointSearch};
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+
+fn main() {
+    let search = FixedPointSearch {
+        range: (-2., 2.),
+        num_seeds: 10000,
+        tolerance: 1E-12,
+        max_iterations: 100,
+    };
+    let fixed_points = find_fixed_points(&MainModel, &search);
+    if fixed_points.is_empty() {
+        println!("No fixed ratios found");
+        return;
+    }
+
+    let params = IntegrationParameters::default();
+    for fixed_point in fixed_points {
+        println!("Ratios {:?}", fixed_point.ratios);
+        println!("    critical exponents {:?}", fixed_point.critical_exponents);
+        if fixed_point.is_uv_attractive() {
+            // Start on the fixed-ratio trajectory at the Planck scale
+            let mut integrator = Integrator::new(params.clone(), Box::new(MainModel), fixed_point.couplings(0.425));
+            let end_point = match integrator.perform_full_integration() {
+                IntegrationResult::Broken { log_scale: scale, .. } => format!("broken at scale {}", scale),
+                _ => "not broken".to_string(),
+            };
+            println!("    {}", end_point);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+
+fn main() {
+    let couplings = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let params = IntegrationParameters {
+        gildener_weinberg: true,
+        ..Default::default()
+    };
+
+    let mut integrator = Integrator::new(params, Box::new(MainModel), couplings);
+    let IntegrationResult::Broken { log_scale: scale, result, violations, gildener_weinberg, .. } =
+        integrator.perform_full_integration()
+    else {
+        println!("Not broken");
+        return;
+    };
+    println!("Broken at scale {}: {:?}, violated: {:?}", scale, result, violations);
+
+    let Some(gildener_weinberg) = gildener_weinberg else {
+        println!("No Gildener-Weinberg analysis for a disallowed breaking");
+        return;
+    };
+    println!("B = {}", gildener_weinberg.b);
+    println!("Radiative minimum: {}", gildener_weinberg.is_minimum());
+    println!("Scalon mass: {} v", gildener_weinberg.scalon_mass);
+
+    // Heavy states in units of the vev
+    let heavy = |masses_squared: &[f64]| -> Vec<f64> {
+        masses_squared.iter().filter(|m2| m2.abs() > 1E-12).map(|m2| m2.signum() * m2.abs().sqrt()).collect()
+    };
+    println!("Vector masses: {:?}", heavy(&gildener_weinberg.vector_masses_squared));
+    println!("Scalar masses: {:?}", heavy(&gildener_weinberg.scalar_masses_squared));
+}
@@ -0,0 +1,61 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, thread};
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::breaking_scale_consumer::BreakingScaleConsumer;
+use hks_method::scanner::consumer::parametrised_consumer::ParametrisedConsumer;
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::scanner::parametrisation::Parametrisation;
+use hks_method::scanner::scanner::SampleSpace;
+use hks_method::simulation;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // The first two angles of the quartics at the norm of the benchmark point
+    let index_x = 2usize;
+    let index_y = 3usize;
+
+    println!("Setting up...");
+    let parametrisation = Parametrisation::Hyperspherical(0);
+    let benchmark = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+    let coordinates = parametrisation.coordinates(&benchmark);
+    let full_ranges = Parametrisation::<7>::hyperspherical_ranges(0, (0.425, 0.425), coordinates[1]);
+    let coordinate_ranges = std::array::from_fn(|i| {
+        if i == index_x || i == index_y { full_ranges[i] } else { (coordinates[i], coordinates[i]) }
+    });
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let consumer: BreakingScaleConsumer<7, 400, 400> = BreakingScaleConsumer::new(coordinate_ranges, index_x, index_y);
+    let consumer = ParametrisedConsumer::new(consumer, parametrisation.clone());
+
+    let mut scanner = MultiThreadedScanner::with_space(
+        SampleSpace::Parametrised(parametrisation, coordinate_ranges),
+        simulation::IntegrationParameters::default(),
+        MainModel,
+        consumer
+    );
+
+    scanner.scan(num_threads, num_samples);
+
+    let (image, min, max) = scanner.consumer.consumer.render();
+
+    // Create out directory
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+
+    let filename = format!("out/angles_{}_{}.png", index_x, index_y);
+    image.save_to_png(&filename).unwrap();
+    let filename = format!("out/angles_{}_{}.txt", index_x, index_y);
+    let file = File::create(filename).unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    println!("Done!");
+}
@@ -0,0 +1,20 @@
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, Integrator};
+
+fn main() {
+    let couplings = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let params = IntegrationParameters::default();
+
+    let mut integrator = Integrator::new(params, Box::new(MainModel), couplings.clone());
+    let comparison = integrator.compare_loop_orders(&couplings);
+    for run in &comparison.runs {
+        let completeness = if run.complete { "" } else { " (incomplete)" };
+        println!("{} loop{}: {:?} at {:?}", run.loop_order.loops(), completeness, run.outcome, run.event_scale);
+    }
+    println!("Outcomes agree: {}", comparison.outcomes_agree());
+    println!("Breaking scale spread: {:?}", comparison.breaking_scale_spread());
+}
@@ -1,9 +1,13 @@
+use std::sync::Arc;
 use std::{env, thread};
 use hks_method::models::main_model::MainModel;
 use hks_method::scanner::consumer::multi_special_allowed_consumer::MultiSpecialAllowedConsumer;
 use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
 use hks_method::simulation;
 
+// Colors of the breaking patterns of MainModel, in order
+const PATTERN_COLORS: [u32; 8] = [0x00FF00, 0x008000, 0x000000, 0x0000FF, 0x00FFFF, 0xFF00FF, 0x800080, 0xFFFF00];
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     let num_samples = args[1].parse::<u64>().unwrap();
@@ -35,22 +39,18 @@ fn main() {
             }
 
             let consumer: MultiSpecialAllowedConsumer<7, 400, 400> = 
-                MultiSpecialAllowedConsumer::new(coupling_ranges, index_x, index_y);
+                MultiSpecialAllowedConsumer::new(coupling_ranges, index_x, index_y, Arc::new(MainModel));
 
             let mut scanner = MultiThreadedScanner::new(
                 coupling_ranges,
-                simulation::IntegrationParameters {
-                    initial_scale: 1.22E19_f64.ln(),
-                    final_scale: 1.0E11_f64.ln(),
-                    num_steps: 1000000,
-                },
+                simulation::IntegrationParameters::default(),
                 MainModel,
                 consumer
             );
 
             scanner.scan(num_threads, num_samples);
 
-            let image = scanner.consumer.render();
+            let image = scanner.consumer.render(&PATTERN_COLORS);
             let filename = format!("out/multi_special_allowed_{}_{}.png", index_x, index_y);
             image.save_to_png(&filename).unwrap();
             
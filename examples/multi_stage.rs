@@ -0,0 +1,21 @@
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, Integrator};
+
+fn main() {
+    let couplings = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let params = IntegrationParameters::default();
+
+    // Continue below the breaking scale down to the electroweak scale
+    let mut integrator = Integrator::new(params, Box::new(MainModel), couplings);
+    let result = integrator.perform_multi_stage_integration(100_f64.ln());
+
+    for stage in result.stages {
+        let theory = stage.pattern.map_or("SO(10)", |pattern| pattern.subgroup);
+        println!("{} from {} to {} in {} steps: {:?}", theory, stage.initial_scale, stage.end_scale, stage.steps, stage.outcome);
+        println!("    {:?} -> {:?}", stage.initial_couplings, stage.end_couplings);
+    }
+}
@@ -0,0 +1,54 @@
+use std::{env, thread};
+use hks_method::models::main_model::MainModel;
+use hks_method::optimiser::{find_extremal_breaking_scale, Extremum, OptimisationMethod, OptimisationParameters};
+use hks_method::simulation::IntegrationParameters;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let extremum = match args.get(1).map(String::as_str) {
+        Some("lowest") => Extremum::Lowest,
+        _ => Extremum::Highest,
+    };
+    let method = match args.get(2).map(String::as_str) {
+        Some("cma-es") => OptimisationMethod::CmaEs,
+        Some("gradient") => OptimisationMethod::Gradient,
+        _ => OptimisationMethod::NelderMead,
+    };
+
+    let point = [0.3, -0.3, 0.1, 0.0, 0.0, 0.0];
+    let mut ranges = [(0.0, 0.0); 7];
+    ranges[0] = (0.425, 0.425);
+    for i in 1..7 {
+        ranges[i] = if i == 5 || i == 6 { (-0.5, 0.5) } else { (point[i - 1], point[i - 1]) };
+    }
+
+    let params = IntegrationParameters::default();
+    let optimisation = OptimisationParameters {
+        ranges,
+        extremum,
+        method,
+        num_threads: thread::available_parallelism()
+            .expect("Failed to get available parallelism")
+            .get(),
+        starts_per_thread: 2,
+        max_evaluations: 200,
+        tolerance: 1E-3,
+        penalty: 1E3,
+        trajectory_samples: 10,
+    };
+
+    println!("Searching the {:?} breaking scale with {:?}...", extremum, method);
+    let optima = find_extremal_breaking_scale(&params, &MainModel, &optimisation);
+    for optimum in optima.iter().take(5) {
+        println!(
+            "Breaking scale {} after {} evaluations at {:?}",
+            optimum.breaking_scale, optimum.evaluations, optimum.couplings.couplings
+        );
+    }
+    if let Some(best) = optima.first() {
+        println!("Running of the best point:");
+        for time_step in &best.trajectory {
+            println!("  {:.4}: {:?}", time_step.log_scale, time_step.couplings.couplings);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, thread};
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::breaking_scale_consumer::BreakingScaleConsumer;
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::scanner::plane::Plane;
+use hks_method::simulation;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    println!("Setting up...");
+    // Through the benchmark point, along lambda_1 + lambda_2 and lambda_6 - lambda_5
+    let plane = Plane::new(
+        Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        },
+        [
+            [0., 1., 1., 0., 0., 0., 0.],
+            [0., 0., 0., 0., 0., -1., 1.],
+        ],
+        (-0.3, 0.3),
+        (-0.3, 0.3),
+    )
+    .expect("The directions span a plane");
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let consumer: BreakingScaleConsumer<7, 400, 400> = BreakingScaleConsumer::in_plane(plane.clone());
+
+    let mut scanner = MultiThreadedScanner::in_plane(
+        plane,
+        simulation::IntegrationParameters::default(),
+        MainModel,
+        consumer
+    );
+
+    scanner.scan(num_threads, num_samples);
+
+    let (image, min, max) = scanner.consumer.render();
+
+    // Create out directory
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+
+    image.save_to_png("out/plane_scale.png").unwrap();
+    let file = File::create("out/plane_scale.txt").unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    println!("Done!");
+}
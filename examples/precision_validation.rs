@@ -0,0 +1,45 @@
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+use hks_method::util::double_double::DoubleDouble;
+use hks_method::util::scalar::Scalar;
+
+fn describe<S: Scalar>(result: IntegrationResult<7, S>) -> String {
+    match result {
+        IntegrationResult::Unbroken(end_point) => format!("Unbroken after {} steps", end_point.steps),
+        IntegrationResult::InitiallyUnstable => "Initially Unstable".to_string(),
+        IntegrationResult::PerturbativityViolated(scale, violation, _) => {
+            format!("Perturbativity Violated at scale {}: {:?}", scale, violation)
+        }
+        IntegrationResult::Broken { log_scale: scale, result: stability_result, violations, end_point, .. } => {
+            format!(
+                "Broken at scale {} after {} steps: {:?}, violated: {:?}",
+                scale, end_point.steps, stability_result, violations
+            )
+        }
+        IntegrationResult::Diverged(scale, divergence) => {
+            format!("Diverged at scale {}: {:?}", scale, divergence)
+        }
+        IntegrationResult::Invalid => "Invalid".to_string(),
+    }
+}
+
+fn params<S: Scalar>() -> IntegrationParameters<S> {
+    IntegrationParameters {
+        certified_stability: true,
+        ..Default::default()
+    }
+}
+
+fn main() {
+    let couplings = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let mut integrator = Integrator::new(params(), Box::new(MainModel), couplings.clone());
+    println!("f64:           {}", describe(integrator.perform_full_integration()));
+
+    let mut integrator: Integrator<7, DoubleDouble> =
+        Integrator::new(params(), Box::new(MainModel), couplings.to_scalar());
+    println!("double-double: {}", describe(integrator.perform_full_integration()));
+}
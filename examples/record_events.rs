@@ -0,0 +1,32 @@
+use std::{env, thread};
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::record_consumer::EventRecordConsumer;
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::simulation;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let mut coupling_ranges = [(-0.5, 0.5); 7];
+    coupling_ranges[0] = (0.425, 0.425);
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let mut scanner = MultiThreadedScanner::new(
+        coupling_ranges,
+        simulation::IntegrationParameters::default(),
+        MainModel,
+        EventRecordConsumer::new(),
+    );
+
+    println!("Scanning...");
+    scanner.scan(num_threads, num_samples);
+
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+    scanner.consumer.save_to_csv("out/events.csv").unwrap();
+
+    println!("Done!");
+}
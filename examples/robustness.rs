@@ -0,0 +1,33 @@
+use std::{env, thread};
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::robustness::{analyse_robustness, Distribution, RobustnessParameters};
+use hks_method::simulation::IntegrationParameters;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let samples_per_thread = args[1].parse::<usize>().unwrap();
+    let width = args.get(2).map_or(0.01, |w| w.parse::<f64>().unwrap());
+
+    // The gauge coupling is kept fixed
+    let mut widths = [width; 7];
+    widths[0] = 0.;
+    let distribution = match args.get(3).map(String::as_str) {
+        Some("box") => Distribution::Box(widths),
+        _ => Distribution::Gaussian(widths),
+    };
+
+    let params = IntegrationParameters::default();
+    let robustness = RobustnessParameters {
+        centre: Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        },
+        distribution,
+        num_threads: thread::available_parallelism()
+            .expect("Failed to get available parallelism")
+            .get(),
+        samples_per_thread,
+    };
+
+    println!("{}", analyse_robustness(&params, &MainModel, &robustness));
+}
@@ -33,11 +33,7 @@ fn main() {
 
     let mut scanner = MultiThreadedScanner::new(
         coupling_ranges,
-        simulation::IntegrationParameters {
-            initial_scale: 1.22E19_f64.ln(),
-            final_scale: 1.0E11_f64.ln(),
-            num_steps: 1000000,
-        },
+        simulation::IntegrationParameters::default(),
         MainModel,
         consumer
     );
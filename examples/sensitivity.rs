@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, thread};
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::plane_gradient_consumer::{allowed_sensitivity, PlaneGradientConsumer};
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let point = [0.3, -0.3, 0.1, 0.0, 0.0, 0.0];
+    let index_x = 5usize;
+    let index_y = 6usize;
+
+    let params = IntegrationParameters {
+        sensitivity: true,
+        ..Default::default()
+    };
+
+    // Sensitivity of the benchmark point
+    let benchmark = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+    let mut integrator = Integrator::new(params.clone(), Box::new(MainModel), benchmark);
+    match integrator.perform_full_integration() {
+        IntegrationResult::Broken { log_scale: scale, end_point, .. } => {
+            let sensitivity = end_point.sensitivity.unwrap();
+            println!("Breaking scale: {}", scale);
+            println!("d t_break / d lambda_i(t0): {:?}", sensitivity.breaking_scale);
+            println!("d lambda_i / d lambda_j(t0) at the breaking:");
+            for row in sensitivity.couplings {
+                println!("  {:?}", row);
+            }
+        }
+        result => println!("No breaking: {:?}", result.outcome()),
+    }
+
+    println!("Setting up...");
+    let mut coupling_ranges = [(0.0, 0.0); 7];
+    coupling_ranges[0] = (0.425, 0.425);
+    for i in 1..7 {
+        if i == index_x || i == index_y {
+            coupling_ranges[i] = (-0.5, 0.5);
+        } else {
+            coupling_ranges[i] = (point[i - 1], point[i - 1]);
+        }
+    }
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let consumer: PlaneGradientConsumer<7, 400, 400> =
+        PlaneGradientConsumer::new(coupling_ranges, index_x, index_y, allowed_sensitivity);
+    let mut scanner = MultiThreadedScanner::new(coupling_ranges, params, MainModel, consumer);
+    scanner.scan(num_threads, num_samples);
+
+    let (image, min, max) = scanner.consumer.render();
+
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+
+    let filename = format!("out/sensitivity_{}_{}.png", index_x, index_y);
+    image.save_to_png(&filename).unwrap();
+    let filename = format!("out/sensitivity_{}_{}.txt", index_x, index_y);
+    let file = File::create(filename).unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    println!("Done!");
+}
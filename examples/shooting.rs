@@ -0,0 +1,37 @@
+use hks_method::model::Couplings;
+use hks_method::models::main_model::MainModel;
+use hks_method::shooting::ShootingParameters;
+use hks_method::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+
+fn main() {
+    // Quartics at the Planck scale, the gauge coupling is only a first guess
+    let guess = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let params = IntegrationParameters::default();
+
+    // Gauge coupling fixed at 10^16 GeV
+    let shooting = ShootingParameters {
+        low_scale: 1.0E16_f64.ln(),
+        low_scale_values: vec![(0, 0.5)],
+        tolerance: 1E-12,
+        max_iterations: 20,
+    };
+
+    let mut integrator = Integrator::new(params, Box::new(MainModel), guess.clone());
+    match integrator.solve_boundary_value_problem(&guess, &shooting) {
+        Ok(solution) => {
+            println!("Converged after {} iterations, residuals {:?}", solution.iterations, solution.residuals);
+            println!("Couplings at the Planck scale: {:?}", solution.couplings.couplings);
+            integrator.reset(&solution.couplings);
+            match integrator.perform_full_integration() {
+                IntegrationResult::Broken { log_scale: scale, result, .. } => {
+                    println!("Broken at scale {}: {:?}", scale, result)
+                }
+                _ => println!("Not broken"),
+            }
+        }
+        Err(failure) => println!("No solution: {:?}", failure),
+    }
+}
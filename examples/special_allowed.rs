@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::{env, thread};
 use hks_method::models::main_model::MainModel;
 use hks_method::scanner::consumer::special_allowed_consumer::SpecialAllowedConsumer;
@@ -27,15 +28,11 @@ fn main() {
         .expect("Failed to get available parallelism")
         .get();
 
-    let consumer: SpecialAllowedConsumer<7, 400, 400> = SpecialAllowedConsumer::new(coupling_ranges, index_x, index_y);
+    let consumer: SpecialAllowedConsumer<7, 400, 400> = SpecialAllowedConsumer::new(coupling_ranges, index_x, index_y, Arc::new(MainModel));
 
     let mut scanner = MultiThreadedScanner::new(
         coupling_ranges,
-        simulation::IntegrationParameters {
-            initial_scale: 1.22E19_f64.ln(),
-            final_scale: 1.0E11_f64.ln(),
-            num_steps: 1000000,
-        },
+        simulation::IntegrationParameters::default(),
         MainModel,
         consumer
     );
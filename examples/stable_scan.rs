@@ -0,0 +1,51 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::{env, thread};
+use hks_method::models::main_model::MainModel;
+use hks_method::scanner::consumer::breaking_scale_consumer::BreakingScaleConsumer;
+use hks_method::scanner::multi_threaded_scanner::MultiThreadedScanner;
+use hks_method::scanner::sampler::Sampling;
+use hks_method::simulation;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let index_x = 1usize;
+    let index_y = 2usize;
+
+    println!("Setting up...");
+    // Every quartic varies, of which only the stable part is sampled
+    let mut coupling_ranges = [(-0.5, 0.5); 7];
+    coupling_ranges[0] = (0.425, 0.425);
+
+    let num_samples = args[1].parse::<u64>().unwrap();
+    let num_threads = thread::available_parallelism()
+        .expect("Failed to get available parallelism")
+        .get();
+
+    let consumer: BreakingScaleConsumer<7, 400, 400> = BreakingScaleConsumer::new(coupling_ranges, index_x, index_y);
+
+    let mut scanner = MultiThreadedScanner::new(
+        coupling_ranges,
+        simulation::IntegrationParameters::default(),
+        MainModel,
+        consumer
+    );
+    scanner.sampling = Sampling::StableHitAndRun(10);
+
+    scanner.scan(num_threads, num_samples);
+
+    let (image, min, max) = scanner.consumer.render();
+
+    // Create out directory
+    std::fs::create_dir_all("out").expect("Failed to create output directory");
+
+    let filename = format!("out/stable_scale_{}_{}.png", index_x, index_y);
+    image.save_to_png(&filename).unwrap();
+    let filename = format!("out/stable_scale_{}_{}.txt", index_x, index_y);
+    let file = File::create(filename).unwrap();
+    let mut writer = BufWriter::new(file);
+    write!(writer, "{} {}", min, max).unwrap();
+
+    println!("Done!");
+}
@@ -0,0 +1,48 @@
+use std::env;
+use std::time::Instant;
+use std::sync::Arc;
+use hks_method::model::{Couplings, LoopOrder};
+use hks_method::models::main_model::MainModel;
+use hks_method::shared_running::SharedRunning;
+use hks_method::simulation::{self, Integrator};
+
+// Integrations per second of the benchmark point, and at two loops with and without the shared
+// running of the gauge coupling
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let num_integrations = args.get(1).map_or(20, |n| n.parse::<usize>().unwrap());
+
+    let params = simulation::IntegrationParameters::default();
+    let couplings = Couplings {
+        couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+    };
+
+    let mut integrator = Integrator::new(params, Box::new(MainModel), couplings.clone());
+    let start = Instant::now();
+    for _ in 0..num_integrations {
+        integrator.reset(&couplings);
+        integrator.perform_full_integration();
+    }
+    let three_loops = num_integrations as f64 / start.elapsed().as_secs_f64();
+    println!("Three loops: {:.3} integrations/s", three_loops);
+
+    let params = simulation::IntegrationParameters { loop_order: LoopOrder::Two, ..Default::default() };
+    let mut integrator = Integrator::new(params.clone(), Box::new(MainModel), couplings.clone());
+    let start = Instant::now();
+    for _ in 0..num_integrations {
+        integrator.reset(&couplings);
+        integrator.perform_full_integration();
+    }
+    let two_loops = num_integrations as f64 / start.elapsed().as_secs_f64();
+    println!("Two loops: {:.3} integrations/s", two_loops);
+
+    let running = SharedRunning::new(&MainModel, &params, &couplings).expect("The gauge coupling runs independently");
+    integrator.share_running(Arc::new(running));
+    let start = Instant::now();
+    for _ in 0..num_integrations {
+        integrator.reset(&couplings);
+        integrator.perform_full_integration();
+    }
+    let shared = num_integrations as f64 / start.elapsed().as_secs_f64();
+    println!("Two loops, shared gauge running: {:.3} integrations/s ({:.2}x)", shared, shared / two_loops);
+}
@@ -0,0 +1,329 @@
+use crate::model::Couplings;
+use crate::simulation::{Integrator, Outcome};
+use crate::util::image::{color_layer, Image};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Couplings origin + s direction for s in range. For a single coupling, use the unit vector of the
+// coupling as direction and set it to 0 in origin, so that s is its value.
+#[derive(Debug, Clone)]
+pub struct ContinuationParameters<const N: usize> {
+    pub origin: Couplings<N>,
+    pub direction: [f64; N],
+    pub range: (f64, f64),
+    pub initial_step: f64,
+    pub min_step: f64,
+    pub max_step: f64,
+    // Steps are halved while the event scale changes by more than this, down to min_step
+    pub max_scale_change: f64,
+    // Changes of the outcome are bisected until they are bracketed to this width in s
+    pub refinement_tolerance: f64,
+}
+impl<const N: usize> ContinuationParameters<N> {
+    pub fn couplings(&self, s: f64) -> Couplings<N> {
+        Couplings {
+            couplings: std::array::from_fn(|i| self.origin.couplings[i] + s * self.direction[i]),
+        }
+    }
+}
+
+// Parameters with which the continuation would not terminate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InvalidContinuation {
+    // initial_step, min_step, max_step or refinement_tolerance is not positive
+    NonPositiveStep(f64),
+    // The lower end of the range lies above the upper one
+    ReversedRange(f64, f64),
+    // An end of the range is infinite or NaN
+    NonFiniteRange(f64, f64),
+    // min_step or refinement_tolerance is below the spacing of f64 at the ends of the range, so
+    // that stepping or bisecting would not change s
+    UnresolvableStep(f64),
+}
+
+#[derive(Debug, Clone)]
+pub struct CurvePoint<const N: usize> {
+    pub s: f64,
+    pub couplings: Couplings<N>,
+    pub outcome: Outcome,
+    pub event_scale: Option<f64>,
+}
+
+// The outcome changes from below to above somewhere within (lower, upper)
+#[derive(Debug, Clone)]
+pub struct Discontinuity {
+    pub lower: f64,
+    pub upper: f64,
+    pub below: Outcome,
+    pub above: Outcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct Curve<const N: usize> {
+    // In ascending order of s
+    pub points: Vec<CurvePoint<N>>,
+    pub discontinuities: Vec<Discontinuity>,
+}
+
+impl<const N: usize> Integrator<N> {
+    // Follows the outcome and event scale along the line of the continuation parameters
+    pub fn continue_breaking_scale(
+        &mut self,
+        continuation: &ContinuationParameters<N>,
+    ) -> Result<Curve<N>, InvalidContinuation> {
+        let (start, end) = continuation.range;
+        if !start.is_finite() || !end.is_finite() {
+            return Err(InvalidContinuation::NonFiniteRange(start, end));
+        }
+        if start > end {
+            return Err(InvalidContinuation::ReversedRange(start, end));
+        }
+        let steps = [
+            continuation.initial_step,
+            continuation.min_step,
+            continuation.max_step,
+            continuation.refinement_tolerance,
+        ];
+        if let Some(&step) = steps.iter().find(|&&step| step.is_nan() || step <= 0.) {
+            return Err(InvalidContinuation::NonPositiveStep(step));
+        }
+        let magnitude = start.abs().max(end.abs());
+        let spacing = magnitude.next_up() - magnitude;
+        for step in [continuation.min_step, continuation.refinement_tolerance] {
+            if step < spacing {
+                return Err(InvalidContinuation::UnresolvableStep(step));
+            }
+        }
+        let mut points = vec![self.curve_point(continuation, start)];
+        let mut discontinuities = Vec::new();
+        let mut step = continuation.initial_step;
+
+        while points.last().unwrap().s < end {
+            let previous = points.last().unwrap().clone();
+            let point = self.curve_point(continuation, (previous.s + step).min(end));
+
+            if point.outcome != previous.outcome {
+                // The continuation resumes just above the discontinuity
+                let (discontinuity, refinements) = self.refine(continuation, previous, point);
+                discontinuities.push(discontinuity);
+                points.extend(refinements);
+                step = continuation.initial_step;
+                continue;
+            } else if let (Some(a), Some(b)) = (previous.event_scale, point.event_scale) {
+                let change = (b - a).abs();
+                if change > continuation.max_scale_change && step > continuation.min_step {
+                    step = (step / 2.).max(continuation.min_step);
+                    continue;
+                }
+                if change < continuation.max_scale_change / 4. {
+                    step = (step * 2.).min(continuation.max_step);
+                }
+            } else {
+                step = (step * 2.).min(continuation.max_step);
+            }
+            points.push(point);
+        }
+
+        points.sort_by(|a, b| a.s.total_cmp(&b.s));
+        Ok(Curve { points, discontinuities })
+    }
+
+    fn curve_point(&mut self, continuation: &ContinuationParameters<N>, s: f64) -> CurvePoint<N> {
+        let couplings = continuation.couplings(s);
+        self.reset(&couplings);
+        let result = self.perform_full_integration();
+        CurvePoint {
+            s,
+            couplings,
+            outcome: result.outcome(),
+            event_scale: result.event_scale(),
+        }
+    }
+
+    // Bisection of a change of the outcome, returning the points evaluated on the way up to the upper
+    // end of the bracket, which comes last
+    fn refine(
+        &mut self,
+        continuation: &ContinuationParameters<N>,
+        mut lower: CurvePoint<N>,
+        mut upper: CurvePoint<N>,
+    ) -> (Discontinuity, Vec<CurvePoint<N>>) {
+        let mut refinements = Vec::new();
+        while upper.s - lower.s > continuation.refinement_tolerance {
+            let middle = self.curve_point(continuation, 0.5 * (lower.s + upper.s));
+            if middle.outcome == lower.outcome {
+                refinements.push(middle.clone());
+                lower = middle;
+            } else {
+                upper = middle;
+            }
+        }
+        let discontinuity = Discontinuity {
+            lower: lower.s,
+            upper: upper.s,
+            below: lower.outcome,
+            above: upper.outcome,
+        };
+        refinements.push(upper);
+        (discontinuity, refinements)
+    }
+}
+
+fn outcome_color(outcome: Outcome) -> u32 {
+    match outcome {
+        Outcome::BrokenAllowed => 0x00FF00,
+        Outcome::BrokenDisallowed => 0xFF0000,
+        Outcome::PerturbativityViolated => 0x000000,
+        _ => 0x0000FF,
+    }
+}
+
+impl<const N: usize> Curve<N> {
+    pub fn save_to_csv(&self, filename: &str) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        let couplings: Vec<String> = (0..N).map(|i| format!("coupling_{}", i)).collect();
+        writeln!(writer, "s,outcome,scale,{}", couplings.join(","))?;
+        for point in &self.points {
+            let scale = point.event_scale.map_or(String::new(), |s| s.to_string());
+            let couplings: Vec<String> = point.couplings.couplings.iter().map(|c| c.to_string()).collect();
+            writeln!(writer, "{},{},{},{}", point.s, point.outcome.name(), scale, couplings.join(","))?;
+        }
+        writer.flush()
+    }
+
+    // Event scale over s, coloured by the outcome, with the discontinuities as grey lines. Returns the
+    // range of the scale axis, which increases upwards.
+    pub fn plot<const NX: usize, const NY: usize>(&self) -> (Image<NX, NY>, f64, f64) {
+        let (s_min, s_max) = (self.points.first().unwrap().s, self.points.last().unwrap().s);
+        let scales = self.points.iter().filter_map(|p| p.event_scale);
+        let (min, max) = scales.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), s| (a.min(s), b.max(s)));
+        let (min, max) = if min < max { (min, max) } else { (min - 1., min + 1.) };
+        // Padding keeps the extreme values inside the pixel grid
+        let (pad_s, pad_scale) = (0.02 * (s_max - s_min), 0.05 * (max - min));
+        let (range_s, range_scale) = ((s_min - pad_s, s_max + pad_s), (max + pad_scale, min - pad_scale));
+
+        let mut layer = Box::new(color_layer::<NX, NY>(range_s, range_scale));
+        // Lines between neighbouring points of the same outcome, only the points across a discontinuity
+        for pair in self.points.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let (Some(scale_a), Some(scale_b)) = (a.event_scale, b.event_scale) else {
+                continue;
+            };
+            let segments = if a.outcome == b.outcome { NX } else { 1 };
+            for k in 0..=segments {
+                let t = k as f64 / segments as f64;
+                let outcome = if t < 0.5 { a.outcome } else { b.outcome };
+                layer.write(a.s + t * (b.s - a.s), scale_a + t * (scale_b - scale_a), outcome_color(outcome));
+            }
+        }
+        for discontinuity in &self.discontinuities {
+            let s = 0.5 * (discontinuity.lower + discontinuity.upper);
+            for k in 0..NY {
+                let scale = range_scale.0 + (k as f64 + 0.5) / NY as f64 * (range_scale.1 - range_scale.0);
+                layer.write(s, scale, 0xC0C0C0);
+            }
+        }
+
+        let mut image = Image::new();
+        image.draw_color_layer(&layer);
+        (image, min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{allowed_violation, stub_params, StubModel, LINEAR_RUNNING};
+    use crate::util::stability::{FinalStabilityResult, StabilityResult};
+
+    // LINEAR_RUNNING with breakings that are allowed for lambda_2 < 0.5 and disallowed above
+    const LINEAR_RUNNING_WITH_DISALLOWED: StubModel<2> = StubModel {
+        stability_condition: |couplings| {
+            let [l1, l2] = couplings.couplings;
+            if l1 > 0. {
+                FinalStabilityResult::Stable
+            } else if l2 < 0.5 {
+                allowed_violation()
+            } else {
+                FinalStabilityResult::UnstableDisallowed(StabilityResult::Violated1([1., 0., 0.]))
+            }
+        },
+        ..LINEAR_RUNNING
+    };
+
+    #[test]
+    fn test_continuation_along_a_coupling() {
+        let origin = Couplings { couplings: [0.5, 0.] };
+        let mut integrator = Integrator::new(stub_params(), Box::new(LINEAR_RUNNING_WITH_DISALLOWED), origin.clone());
+        // lambda_2 from 0.02 (breaking below final_scale) to 1
+        let continuation = ContinuationParameters {
+            origin,
+            direction: [0., 1.],
+            range: (0.02, 1.),
+            initial_step: 0.1,
+            min_step: 1E-3,
+            max_step: 0.2,
+            max_scale_change: 0.5,
+            refinement_tolerance: 1E-6,
+        };
+        let curve = integrator.continue_breaking_scale(&continuation).unwrap();
+
+        let [unbroken, disallowed] = &curve.discontinuities[..] else {
+            panic!("Expected two discontinuities: {:?}", curve.discontinuities);
+        };
+        // No breaking above t = 0 below lambda_2 = 0.05
+        assert_eq!((unbroken.below, unbroken.above), (Outcome::Unbroken, Outcome::BrokenAllowed));
+        assert!(unbroken.lower <= 0.05 + 1E-3 && unbroken.upper >= 0.05 - 1E-3);
+        assert!(unbroken.upper - unbroken.lower <= 1E-6);
+        assert_eq!((disallowed.below, disallowed.above), (Outcome::BrokenAllowed, Outcome::BrokenDisallowed));
+        assert!(disallowed.lower < 0.5 && disallowed.upper >= 0.5 && disallowed.upper - disallowed.lower <= 1E-6);
+
+        assert!(curve.points.windows(2).all(|p| p[0].s < p[1].s));
+        for pair in curve.points.windows(2) {
+            if let (Some(a), Some(b)) = (pair[0].event_scale, pair[1].event_scale)
+                && pair[0].outcome == pair[1].outcome
+                && pair[1].s - pair[0].s > continuation.min_step
+            {
+                assert!((b - a).abs() <= continuation.max_scale_change);
+            }
+        }
+        let (_image, min, max) = curve.plot::<100, 50>();
+        assert!(min < max);
+    }
+
+    #[test]
+    fn test_invalid_continuation() {
+        let origin = Couplings { couplings: [0.5, 0.] };
+        let mut integrator = Integrator::new(stub_params(), Box::new(LINEAR_RUNNING), origin.clone());
+        let valid = ContinuationParameters {
+            origin,
+            direction: [0., 1.],
+            range: (0.02, 1.),
+            initial_step: 0.1,
+            min_step: 1E-3,
+            max_step: 0.2,
+            max_scale_change: 0.5,
+            refinement_tolerance: 1E-6,
+        };
+        let cases = [
+            (
+                ContinuationParameters { range: (1., 0.02), ..valid.clone() },
+                InvalidContinuation::ReversedRange(1., 0.02),
+            ),
+            (ContinuationParameters { initial_step: 0., ..valid.clone() }, InvalidContinuation::NonPositiveStep(0.)),
+            (ContinuationParameters { min_step: -1E-3, ..valid.clone() }, InvalidContinuation::NonPositiveStep(-1E-3)),
+            (
+                ContinuationParameters { range: (0., f64::INFINITY), ..valid.clone() },
+                InvalidContinuation::NonFiniteRange(0., f64::INFINITY),
+            ),
+            (ContinuationParameters { min_step: 1E-17, ..valid.clone() }, InvalidContinuation::UnresolvableStep(1E-17)),
+            (
+                ContinuationParameters { refinement_tolerance: 1E-17, ..valid.clone() },
+                InvalidContinuation::UnresolvableStep(1E-17),
+            ),
+        ];
+        for (continuation, error) in cases {
+            assert_eq!(integrator.continue_breaking_scale(&continuation).unwrap_err(), error);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::model::{Couplings, Model};
+use crate::scanner::scanner::CouplingRanges;
+use crate::util::linear_algebra::{eigenvalues, solve_linear, Matrix};
+
+// Fixed points whose ratios all agree to this accuracy are the same
+const DUPLICATE_TOLERANCE: f64 = 1E-6;
+
+// Flow of the ratios x_i = lambda_i / g^2 of the couplings to the square of the gauge coupling
+// couplings[0], in the time tau with d tau = g^2 dt / (16 pi^2),
+//     dx_i / d tau = beta_i(1, x) - 2 x_i beta_g(1, x)
+// with the one-loop beta functions. Higher loops are suppressed by further powers of g and do not
+// change the flow in the UV of an asymptotically free theory. The entry of the gauge coupling is
+// ignored and vanishes.
+pub fn rescaled_flow<const N: usize>(model: &dyn Model<N>, ratios: &[f64; N]) -> [f64; N] {
+    let mut couplings = *ratios;
+    couplings[0] = 1.;
+    let beta_functions = model.beta_function(&Couplings { couplings });
+    let gauge = beta_functions[0].b1;
+    std::array::from_fn(|i| if i == 0 { 0. } else { beta_functions[i].b1 - 2. * couplings[i] * gauge })
+}
+
+// d(rescaled_flow)_i / dx_j for i, j > 0, from the Jacobian of the beta functions
+fn stability_matrix<const N: usize>(model: &dyn Model<N>, ratios: &[f64; N]) -> Matrix {
+    let mut couplings = *ratios;
+    couplings[0] = 1.;
+    let couplings = Couplings { couplings };
+    let gauge = model.beta_function(&couplings)[0].b1;
+    let jacobian = model.jacobian(&couplings);
+    let mut matrix = Matrix::zeros(N - 1, N - 1);
+    for i in 1..N {
+        for j in 1..N {
+            let diagonal = if i == j { 2. * gauge } else { 0. };
+            matrix[(i - 1, j - 1)] = jacobian[i][j].b1 - 2. * couplings.couplings[i] * jacobian[0][j].b1 - diagonal;
+        }
+    }
+    matrix
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedPoint<const N: usize> {
+    // lambda_i / g^2, with 1 for the gauge coupling
+    pub ratios: [f64; N],
+    // Eigenvalues (re, im) of the stability matrix of the rescaled flow, in ascending order of the
+    // real part. Perturbations along eigenvectors with negative real part die out towards the UV.
+    pub critical_exponents: Vec<(f64, f64)>,
+}
+impl<const N: usize> FixedPoint<N> {
+    pub fn uv_attractive_directions(&self) -> usize {
+        self.critical_exponents.iter().filter(|(re, _)| *re < 0.).count()
+    }
+
+    // Every trajectory close to the fixed point approaches it in the UV
+    pub fn is_uv_attractive(&self) -> bool {
+        self.uv_attractive_directions() == N - 1
+    }
+
+    // Couplings on the fixed-ratio trajectory at the given gauge coupling
+    pub fn couplings(&self, gauge_coupling: f64) -> Couplings<N> {
+        let g2 = gauge_coupling * gauge_coupling;
+        Couplings {
+            couplings: std::array::from_fn(|i| if i == 0 { gauge_coupling } else { self.ratios[i] * g2 }),
+        }
+    }
+
+    // Ranges for a scan around the fixed-ratio trajectory, the quartic couplings varied by the given
+    // fraction of max(|lambda_i|, g^2)
+    pub fn coupling_ranges(&self, gauge_coupling: f64, relative_width: f64) -> CouplingRanges<N> {
+        let couplings = self.couplings(gauge_coupling).couplings;
+        let g2 = gauge_coupling * gauge_coupling;
+        std::array::from_fn(|i| {
+            if i == 0 {
+                (gauge_coupling, gauge_coupling)
+            } else {
+                let width = relative_width * couplings[i].abs().max(g2);
+                (couplings[i] - width, couplings[i] + width)
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FixedPointSearch {
+    // Newton's method is started from num_seeds points with every ratio drawn uniformly from range
+    pub range: (f64, f64),
+    pub num_seeds: usize,
+    // Largest component of the rescaled flow at a fixed point
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+// Distinct fixed points of the rescaled flow found from the seeds, the most UV-attractive first
+pub fn find_fixed_points<const N: usize>(model: &dyn Model<N>, search: &FixedPointSearch) -> Vec<FixedPoint<N>> {
+    let mut fixed_points: Vec<FixedPoint<N>> = Vec::new();
+    for _ in 0..search.num_seeds {
+        let (min, max) = search.range;
+        let seed = std::array::from_fn(|i| if i == 0 { 1. } else { rand::random::<f64>() * (max - min) + min });
+        let Some(ratios) = newton(model, seed, search) else {
+            continue;
+        };
+        let duplicate = fixed_points
+            .iter()
+            .any(|f| f.ratios.iter().zip(&ratios).all(|(a, b)| (a - b).abs() <= DUPLICATE_TOLERANCE * a.abs().max(1.)));
+        if duplicate {
+            continue;
+        }
+
+        // Fixed points whose critical exponents cannot be determined are skipped like failed seeds
+        let Some(mut critical_exponents) = eigenvalues(&stability_matrix(model, &ratios)) else {
+            continue;
+        };
+        critical_exponents.sort_by(|a, b| a.0.total_cmp(&b.0));
+        fixed_points.push(FixedPoint {
+            ratios,
+            critical_exponents,
+        });
+    }
+    fixed_points.sort_by_key(|f| std::cmp::Reverse(f.uv_attractive_directions()));
+    fixed_points
+}
+
+fn newton<const N: usize>(model: &dyn Model<N>, mut ratios: [f64; N], search: &FixedPointSearch) -> Option<[f64; N]> {
+    for _ in 0..search.max_iterations {
+        let flow = rescaled_flow(model, &ratios);
+        if !flow.iter().all(|x| x.is_finite()) {
+            return None;
+        }
+        if flow.iter().all(|x| x.abs() <= search.tolerance) {
+            return Some(ratios);
+        }
+        let step = solve_linear(stability_matrix(model, &ratios), flow[1..].iter().map(|x| -x).collect()).ok()?;
+        for (ratio, change) in ratios[1..].iter_mut().zip(step) {
+            *ratio += change;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::BetaFunctionValue;
+    use crate::test_support::{stable, StubModel};
+
+    // beta_g = -g^3, beta_lambda = g^4 - 6 g^2 lambda + lambda^2 at one loop, with the fixed ratios
+    // x = 2 +- sqrt(3) and critical exponents 2 x - 4
+    const SINGLE_QUARTIC: StubModel<2> = StubModel {
+        beta_function: |couplings| {
+            let [g, l] = couplings.couplings;
+            [
+                BetaFunctionValue { b1: -g.powi(3), b2: 0., b3: 0. },
+                BetaFunctionValue { b1: g.powi(4) - 6. * g * g * l + l * l, b2: 0., b3: 0. },
+            ]
+        },
+        stability_condition: stable,
+    };
+
+    #[test]
+    fn test_fixed_points_and_critical_exponents() {
+        let search = FixedPointSearch {
+            range: (-5., 5.),
+            num_seeds: 100,
+            tolerance: 1E-12,
+            max_iterations: 100,
+        };
+        let fixed_points = find_fixed_points(&SINGLE_QUARTIC, &search);
+        assert_eq!(fixed_points.len(), 2);
+
+        let sqrt_3 = 3_f64.sqrt();
+        let [attractive, repulsive] = &fixed_points[..] else { unreachable!() };
+        assert!(attractive.is_uv_attractive() && !repulsive.is_uv_attractive());
+        for (fixed_point, ratio) in [(attractive, 2. - sqrt_3), (repulsive, 2. + sqrt_3)] {
+            assert!((fixed_point.ratios[1] - ratio).abs() < 1E-10);
+            let (re, im) = fixed_point.critical_exponents[0];
+            assert!((re - (2. * ratio - 4.)).abs() < 1E-6 && im == 0.);
+        }
+        let [g, l] = attractive.couplings(0.5).couplings;
+        assert!(g == 0.5 && (l - 0.25 * (2. - sqrt_3)).abs() < 1E-12);
+    }
+}
@@ -1,6 +1,16 @@
 
+pub mod continuation;
+pub mod fixed_point;
 pub mod model;
 pub mod models;
+pub mod multi_stage;
+pub mod optimiser;
+pub mod robustness;
 pub mod scanner;
+pub mod sensitivity;
+pub mod shared_running;
+pub mod shooting;
 pub mod simulation;
+#[cfg(test)]
+mod test_support;
 pub mod util;
\ No newline at end of file
@@ -1,29 +1,266 @@
-use crate::util::constants::{PI_4_2, PI_4_4, PI_4_6};
-use crate::util::stability::FinalStabilityResult;
+use crate::multi_stage::Threshold;
+use crate::util::effective_potential::{GildenerWeinberg, MassMatrices};
+use crate::util::interval::Precision;
+use crate::util::lie_algebra::{GaugeAlgebra, UnbrokenSubalgebra};
+use crate::util::scalar::Scalar;
+use crate::util::stability::{Certified, FinalStabilityResult, StabilityCondition, StabilityViolation};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BreakingPattern {
+    // Residual symmetry group of the vacuum
+    pub subgroup: &'static str,
+    // Dimension and rank of the residual group
+    pub dimension: usize,
+    pub rank: usize,
+    // Whether the residual group is larger than the one the model is meant to break to
+    pub supergroup: bool,
+}
+
+// Number of loops after which the beta functions are truncated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoopOrder {
+    One = 1,
+    Two = 2,
+    Three = 3,
+}
+impl LoopOrder {
+    pub const ALL: [LoopOrder; 3] = [LoopOrder::One, LoopOrder::Two, LoopOrder::Three];
+
+    pub fn loops(self) -> usize {
+        self as usize
+    }
+}
 
 #[derive(Debug)]
-pub struct BetaFunctionValue {
-    pub b1: f64,
-    pub b2: f64,
-    pub b3: f64,
+pub struct BetaFunctionValue<S: Scalar = f64> {
+    pub b1: S,
+    pub b2: S,
+    pub b3: S,
 }
-impl BetaFunctionValue {
-    pub fn compute(&self) -> f64 {
-        self.b1 / PI_4_2 + self.b2 / PI_4_4 + self.b3 / PI_4_6
+impl<S: Scalar> BetaFunctionValue<S> {
+    pub fn compute(&self) -> S {
+        self.loop_terms().total()
     }
+
+    // Beta function truncated after the given loop order
+    pub fn compute_to_order(&self, loop_order: LoopOrder) -> S {
+        self.loop_terms().to_order(loop_order)
+    }
+
+    pub fn loop_terms(&self) -> LoopTerms<S> {
+        let loop_factor = S::loop_factor();
+        let loop_factor_2 = loop_factor * loop_factor;
+        LoopTerms {
+            one: self.b1 / loop_factor,
+            two: self.b2 / loop_factor_2,
+            three: self.b3 / (loop_factor * loop_factor_2),
+        }
+    }
+}
+
+// Contributions of the loop orders to a beta function, with the loop factors applied
+#[derive(Debug, Clone, Copy)]
+pub struct LoopTerms<S: Scalar = f64> {
+    pub one: S,
+    pub two: S,
+    pub three: S,
+}
+impl<S: Scalar> LoopTerms<S> {
+    pub fn zero() -> Self {
+        let zero = S::from_f64(0.);
+        Self { one: zero, two: zero, three: zero }
+    }
+
+    pub fn total(&self) -> S {
+        self.one + self.two + self.three
+    }
+
+    pub fn to_order(&self, loop_order: LoopOrder) -> S {
+        match loop_order {
+            LoopOrder::One => self.one,
+            LoopOrder::Two => self.one + self.two,
+            LoopOrder::Three => self.total(),
+        }
+    }
+
+    // Adds the absolute values of the terms of other
+    pub fn add_abs(&mut self, other: &Self) {
+        self.one += other.one.abs();
+        self.two += other.two.abs();
+        self.three += other.three.abs();
+    }
+}
+
+// d beta_i / d lambda_j at every loop order, indexed [i][j]
+pub type Jacobian<const N: usize, S = f64> = [[BetaFunctionValue<S>; N]; N];
+
+// Jacobian of the beta functions from central differences, with steps balancing truncation and
+// round-off error
+pub fn finite_difference_jacobian<const N: usize, S: Scalar, M: Model<N, S> + ?Sized>(
+    model: &M,
+    couplings: &Couplings<N, S>,
+) -> Jacobian<N, S> {
+    let columns: [[BetaFunctionValue<S>; N]; N] = std::array::from_fn(|j| {
+        let coupling = couplings.couplings[j];
+        let magnitude = if coupling.abs() > S::from_f64(1.) { coupling.abs() } else { S::from_f64(1.) };
+        let step = S::from_f64(S::EPSILON.cbrt()) * magnitude;
+        let (mut up, mut down) = (couplings.clone(), couplings.clone());
+        up.couplings[j] += step;
+        down.couplings[j] += -step;
+        let (up, down) = (model.beta_function(&up), model.beta_function(&down));
+        let width = step + step;
+        std::array::from_fn(|i| BetaFunctionValue {
+            b1: (up[i].b1 - down[i].b1) / width,
+            b2: (up[i].b2 - down[i].b2) / width,
+            b3: (up[i].b3 - down[i].b3) / width,
+        })
+    });
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| BetaFunctionValue {
+            b1: columns[j][i].b1,
+            b2: columns[j][i].b2,
+            b3: columns[j][i].b3,
+        })
+    })
+}
+
+// Largest deviation of model.jacobian from finite differences, relative to the largest entry of the
+// same loop order, to test implementations of Model::jacobian
+pub fn jacobian_deviation<const N: usize, M: Model<N> + ?Sized>(model: &M, couplings: &Couplings<N>) -> f64 {
+    let analytic = model.jacobian(couplings);
+    let numeric = finite_difference_jacobian(model, couplings);
+    let terms = |jacobian: &Jacobian<N>, order: usize| -> Vec<f64> {
+        jacobian
+            .iter()
+            .flatten()
+            .map(|value| match order {
+                1 => value.b1,
+                2 => value.b2,
+                _ => value.b3,
+            })
+            .collect()
+    };
+    (1..=3)
+        .map(|order| {
+            let (analytic, numeric) = (terms(&analytic, order), terms(&numeric, order));
+            let scale = numeric.iter().fold(0_f64, |a, b| a.max(b.abs())).max(f64::MIN_POSITIVE);
+            analytic.iter().zip(&numeric).fold(0_f64, |a, (x, y)| a.max((x - y).abs() / scale))
+        })
+        .fold(0., f64::max)
 }
 
 #[derive(Debug, Clone)]
-pub struct Couplings<const N: usize> {
-    pub couplings: [f64; N],
+pub struct Couplings<const N: usize, S: Scalar = f64> {
+    pub couplings: [S; N],
+}
+impl<const N: usize> Couplings<N> {
+    pub fn to_scalar<S: Scalar>(&self) -> Couplings<N, S> {
+        Couplings {
+            couplings: self.couplings.map(S::from_f64),
+        }
+    }
 }
 
-pub struct TimeStep<const N: usize> {
+#[derive(Debug, Clone)]
+pub struct TimeStep<const N: usize, S: Scalar = f64> {
     pub log_scale: f64,
-    pub couplings: Couplings<N>,
+    pub couplings: Couplings<N, S>,
 }
 
-pub trait Model<const N: usize> {
-    fn beta_function(&self, couplings: &Couplings<N>) -> [BetaFunctionValue; N];
-    fn stability_condition(&self, couplings: &Couplings<N>) -> FinalStabilityResult;
+pub trait Model<const N: usize, S: Scalar = f64> {
+    fn beta_function(&self, couplings: &Couplings<N, S>) -> [BetaFunctionValue<S>; N];
+    // d beta_i / d lambda_j, by finite differences unless the model knows it exactly
+    fn jacobian(&self, couplings: &Couplings<N, S>) -> Jacobian<N, S> {
+        finite_difference_jacobian(self, couplings)
+    }
+    // Highest loop order of the beta function of each coupling that is known, higher orders of the
+    // BetaFunctionValue are zero
+    fn loop_orders(&self) -> [LoopOrder; N] {
+        [LoopOrder::Three; N]
+    }
+    // Couplings whose beta functions truncated at the loop order only depend on each other, so that
+    // their running can be precomputed once and shared by integrations, see SharedRunning
+    fn independent_couplings(&self, _loop_order: LoopOrder) -> &'static [usize] {
+        &[]
+    }
+    // beta_function without evaluating the couplings that are independent at some loop order, whose
+    // entries are unspecified and are taken from a SharedRunning instead
+    fn dependent_beta_function(&self, couplings: &Couplings<N, S>) -> [BetaFunctionValue<S>; N] {
+        self.beta_function(couplings)
+    }
+    // Violation of the first of the stability_conditions that does not hold
+    fn stability_condition(&self, couplings: &Couplings<N, S>) -> FinalStabilityResult;
+
+    fn stability_conditions(&self) -> &'static [StabilityCondition];
+    // Every one of the stability_conditions that does not hold, in order
+    fn violated_stability_conditions(&self, couplings: &Couplings<N, S>) -> Vec<StabilityViolation>;
+
+    // Every pattern classify_vacuum can return
+    fn breaking_patterns(&self) -> &'static [BreakingPattern];
+    // Residual symmetry along the direction in which the potential turns unstable, only known for
+    // violations of allowed stability conditions
+    fn classify_vacuum(&self, violation: &StabilityViolation) -> Option<BreakingPattern>;
+
+    // Effective theory below the breaking scale of one of the breaking_patterns, together with the
+    // matching of the couplings
+    fn daughter(&self, _pattern: &BreakingPattern) -> Option<&'static dyn Threshold<N>> {
+        None
+    }
+
+    // Gauge algebra with the representations of the scalar fields
+    fn gauge_algebra(&self) -> Option<&'static GaugeAlgebra> {
+        None
+    }
+    // Vev of every representation of the gauge_algebra along the direction in which the potential
+    // turns unstable
+    fn vacuum_fields(&self, _violation: &StabilityViolation) -> Option<Vec<Vec<f64>>> {
+        None
+    }
+    fn unbroken_subalgebra(&self, violation: &StabilityViolation, gauge_coupling: f64) -> Option<UnbrokenSubalgebra> {
+        let algebra = self.gauge_algebra()?;
+        let fields = self.vacuum_fields(violation)?;
+        Some(algebra.unbroken_subalgebra(&fields, gauge_coupling))
+    }
+
+    // Field-dependent mass matrices for the given vevs of the representations of the gauge_algebra
+    fn mass_matrices(&self, _couplings: &Couplings<N, S>, _fields: &[Vec<f64>]) -> Option<MassMatrices> {
+        None
+    }
+    fn gildener_weinberg(&self, couplings: &Couplings<N, S>, violation: &StabilityViolation) -> Option<GildenerWeinberg> {
+        let mut fields = self.vacuum_fields(violation)?;
+        let norm = fields.iter().flatten().map(|x| x * x).sum::<f64>().sqrt();
+        fields.iter_mut().flatten().for_each(|x| *x /= norm);
+        let masses = self.mass_matrices(couplings, &fields)?;
+        Some(GildenerWeinberg::new(&masses, &fields.concat()))
+    }
+
+    // Interval arithmetic version of stability_condition that only decides when the result is
+    // guaranteed to be independent of round-off. Decides the first violated condition, None if the
+    // potential is stable.
+    fn certified_stability_condition(
+        &self,
+        _couplings: &Couplings<N, S>,
+        _precision: Precision,
+    ) -> Certified<Option<StabilityViolation>> {
+        Certified::Undecided
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::main_model::MainModel;
+    use crate::models::toy_model::ToyModel;
+
+    #[test]
+    fn test_exact_jacobians() {
+        let couplings = Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.05, 0.1, -0.05],
+        };
+        assert!(jacobian_deviation(&MainModel, &couplings) < 1E-8);
+        let couplings = Couplings {
+            couplings: [0.5, 0.2, -0.1],
+        };
+        assert!(jacobian_deviation(&ToyModel, &couplings) < 1E-8);
+    }
 }
@@ -0,0 +1,44 @@
+use crate::model::{BetaFunctionValue, BreakingPattern, Couplings, LoopOrder, Model};
+use crate::util::scalar::Scalar;
+use crate::util::stability::{FinalStabilityResult, StabilityCondition, StabilityViolation};
+
+// Gauge couplings of a product group below a breaking scale, once all scalars apart from the
+// scalon have decoupled. Run at one loop, beta(g_i) = b_i g_i^3 / (16 pi^2).
+#[derive(Debug, Clone)]
+pub struct GaugeTheory<const N: usize> {
+    pub coefficients: [f64; N],
+}
+impl<const N: usize, S: Scalar> Model<N, S> for GaugeTheory<N> {
+    fn beta_function(&self, couplings: &Couplings<N, S>) -> [BetaFunctionValue<S>; N] {
+        std::array::from_fn(|i| BetaFunctionValue {
+            b1: S::from_f64(self.coefficients[i]) * couplings.couplings[i].powi(3),
+            b2: S::from_f64(0.),
+            b3: S::from_f64(0.),
+        })
+    }
+
+    fn loop_orders(&self) -> [LoopOrder; N] {
+        [LoopOrder::One; N]
+    }
+
+    // Without light scalars there is no potential that could turn unstable
+    fn stability_condition(&self, _couplings: &Couplings<N, S>) -> FinalStabilityResult {
+        FinalStabilityResult::Stable
+    }
+
+    fn stability_conditions(&self) -> &'static [StabilityCondition] {
+        &[]
+    }
+
+    fn violated_stability_conditions(&self, _couplings: &Couplings<N, S>) -> Vec<StabilityViolation> {
+        Vec::new()
+    }
+
+    fn breaking_patterns(&self) -> &'static [BreakingPattern] {
+        &[]
+    }
+
+    fn classify_vacuum(&self, _violation: &StabilityViolation) -> Option<BreakingPattern> {
+        None
+    }
+}
@@ -1,47 +1,64 @@
-use crate::model::{BetaFunctionValue, Couplings, Model};
-use crate::util::stability::{stab3vev, FinalStabilityResult, StabilityResult};
+use crate::model::{BetaFunctionValue, BreakingPattern, Couplings, Jacobian, LoopOrder, Model};
+use crate::models::gauge_theory::GaugeTheory;
+use crate::models::so10;
+use crate::multi_stage::{Daughter, Threshold};
+use crate::util::double_double::DoubleDouble;
+use crate::util::dual::exact_jacobian;
+use crate::util::interval::{Interval, IntervalBound, Precision};
+use crate::util::effective_potential::MassMatrices;
+use crate::util::lie_algebra::GaugeAlgebra;
+use crate::util::linear_algebra::Matrix;
+use crate::util::scalar::Scalar;
+use crate::util::stability::{
+    stab3vev, stab3vev_certified, Certified, FinalStabilityResult, StabilityCondition, StabilityResult,
+    StabilityViolation,
+};
+use std::ops::{Add, Div, Mul};
 
 #[derive(Debug, Clone)]
 pub struct MainModel;
-impl Model<7> for MainModel {
-    fn beta_function(&self, couplings: &Couplings<7>) -> [BetaFunctionValue; 7] {
+impl<S: Scalar> Model<7, S> for MainModel
+where
+    f64: Mul<S, Output = S>,
+{
+    fn beta_function(&self, couplings: &Couplings<7, S>) -> [BetaFunctionValue<S>; 7] {
+        let mut beta_functions = self.dependent_beta_function(couplings);
+        beta_functions[0] = gauge_beta_function(couplings);
+        beta_functions
+    }
+
+    // The quartics enter the running of the gauge coupling only at three loops
+    fn independent_couplings(&self, loop_order: LoopOrder) -> &'static [usize] {
+        match loop_order {
+            LoopOrder::Three => &[],
+            LoopOrder::One | LoopOrder::Two => &[0],
+        }
+    }
+
+    fn dependent_beta_function(&self, couplings: &Couplings<7, S>) -> [BetaFunctionValue<S>; 7] {
+        let q = S::rational;
         let [g, l1, l2, l6, l7, l8, l9] = couplings.couplings;
 
         let g2 = g.powi(2);
-        let g3 = g.powi(3);
         let g4 = g.powi(4);
-        let g5 = g.powi(5);
 
         let l8_2 = l8.powi(2);
         let l9_2 = l9.powi(2);
 
         [
             BetaFunctionValue {
-                b1: (-70.0 / 3.0) * g3,
-                b2: (-1757.0 / 6.0) * g5,
-                b3: (1077557.0 / 1728.0) * g.powi(7) + 640.0 * g5 * l1 - 3008.0 * g3 * l1.powi(2)
-                    + 272.0 * g5 * l2
-                    - 3296.0 * g3 * l1 * l2
-                    - 1532.0 * g3 * l2.powi(2)
-                    + 180.0 * g5 * l6
-                    - 1088.0 * g3 * l6.powi(2)
-                    + 132.0 * g5 * l7
-                    - 640.0 * g3 * l6 * l7
-                    - 1280.0 * g3 * l7.powi(2)
-                    + 256.0 * g5 * l8
-                    - 3488.0 * g3 * l8_2
-                    + 512.0 * g5 * l9
-                    - 1744.0 * g3 * l8 * l9
-                    - 6322.0 * g3 * l9_2,
+                b1: S::from_f64(0.),
+                b2: S::from_f64(0.),
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (27.0 / 4.0) * g4 - 96.0 * g2 * l1
+                b1: q(27.0, 4.0) * g4 - 96.0 * g2 * l1
                     + 424.0 * l1.powi(2)
                     + 412.0 * l1 * l2
-                    + (279.0 / 2.0) * l2.powi(2)
+                    + q(279.0, 2.0) * l2.powi(2)
                     + 256.0 * l8_2
                     + 128.0 * l8 * l9,
-                b2: 51.0 * g.powi(6) - (728.0 / 3.0) * g4 * l1 + 25600.0 * g2 * l1.powi(2)
+                b2: 51.0 * g.powi(6) - q(728.0, 3.0) * g4 * l1 + 25600.0 * g2 * l1.powi(2)
                     - 28608.0 * l1.powi(3)
                     + 648.0 * g4 * l2
                     + 26368.0 * g2 * l1 * l2
@@ -62,11 +79,11 @@ impl Model<7> for MainModel {
                     - 5952.0 * l2 * l9_2
                     - 14848.0 * l8 * l9_2
                     - 1536.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
                 b1: -3.0 * g4 - 96.0 * g2 * l2 + 96.0 * l1 * l2 - 4.0 * l2.powi(2) + 64.0 * l9_2,
-                b2: (-68.0 / 3.0) * g.powi(6) - 96.0 * g4 * l1 - (4544.0 / 3.0) * g4 * l2
+                b2: q(-68.0, 3.0) * g.powi(6) - 96.0 * g4 * l1 - q(4544.0, 3.0) * g4 * l2
                     + 3072.0 * g2 * l1 * l2
                     - 19648.0 * l1.powi(2) * l2
                     - 304.0 * g2 * l2.powi(2)
@@ -80,20 +97,20 @@ impl Model<7> for MainModel {
                     + 5248.0 * l2 * l9_2
                     - 4096.0 * l8 * l9_2
                     - 8704.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (315.0 / 128.0) * g4 - (135.0 / 2.0) * g2 * l6
+                b1: q(315.0, 128.0) * g4 - q(135.0, 2.0) * g2 * l6
                     + 320.0 * l6.powi(2)
                     + 160.0 * l6 * l7
                     + 80.0 * l7.powi(2)
                     + 360.0 * l8_2
                     + 180.0 * l8 * l9
-                    + (105.0 / 2.0) * l9_2,
-                b2: (45925.0 / 512.0) * g.powi(6) - (26475.0 / 32.0) * g4 * l6
+                    + q(105.0, 2.0) * l9_2,
+                b2: q(45925.0, 512.0) * g.powi(6) - q(26475.0, 32.0) * g4 * l6
                     + 13320.0 * g2 * l6.powi(2)
                     - 21120.0 * l6.powi(3)
-                    + (1665.0 / 8.0) * g4 * l7
+                    + q(1665.0, 8.0) * g4 * l7
                     + 7200.0 * g2 * l6 * l7
                     - 14080.0 * l6.powi(2) * l7
                     + 2160.0 * g2 * l7.powi(2)
@@ -112,11 +129,11 @@ impl Model<7> for MainModel {
                     - 3840.0 * l7 * l9_2
                     - 24240.0 * l8 * l9_2
                     - 9300.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (9.0 / 8.0) * g4 - (135.0 / 2.0) * g2 * l7 + 96.0 * l6 * l7 + 24.0 * l9_2,
-                b2: (479.0 / 32.0) * g.powi(6) + 36.0 * g4 * l6 - (44511.0 / 32.0) * g4 * l7
+                b1: q(9.0, 8.0) * g4 - q(135.0, 2.0) * g2 * l7 + 96.0 * l6 * l7 + 24.0 * l9_2,
+                b2: q(479.0, 32.0) * g.powi(6) + 36.0 * g4 * l6 - q(44511.0, 32.0) * g4 * l7
                     + 2160.0 * g2 * l6 * l7
                     - 15488.0 * l6.powi(2) * l7
                     + 576.0 * g2 * l7.powi(2)
@@ -130,26 +147,26 @@ impl Model<7> for MainModel {
                     + 3084.0 * l7 * l9_2
                     - 1536.0 * l8 * l9_2
                     - 3264.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (9.0 / 8.0) * g4 - (327.0 / 4.0) * g2 * l8
+                b1: q(9.0, 8.0) * g4 - q(327.0, 4.0) * g2 * l8
                     + 376.0 * l1 * l8
                     + 206.0 * l2 * l8
                     + 272.0 * l6 * l8
                     + 80.0 * l7 * l8
                     + 32.0 * l8_2
                     + 90.0 * l1 * l9
-                    + (93.0 / 2.0) * l2 * l9
+                    + q(93.0, 2.0) * l2 * l9
                     + 64.0 * l6 * l9
                     + 16.0 * l7 * l9
                     + 24.0 * l9_2,
-                b2: (5287.0 / 64.0) * g.powi(6)
+                b2: q(5287.0, 64.0) * g.powi(6)
                     + 465.0 * g4 * l1
-                    + (1005.0 / 4.0) * g4 * l2
+                    + q(1005.0, 4.0) * g4 * l2
                     + 335.0 * g4 * l6
                     + 95.0 * g4 * l7
-                    - (129865.0 / 192.0) * g4 * l8
+                    - q(129865.0, 192.0) * g4 * l8
                     + 24064.0 * g2 * l1 * l8
                     - 7520.0 * l1.powi(2) * l8
                     + 13184.0 * g2 * l2 * l8
@@ -166,7 +183,7 @@ impl Model<7> for MainModel {
                     - 13056.0 * l6 * l8_2
                     - 3840.0 * l7 * l8_2
                     - 2976.0 * l8.powi(3)
-                    + (477.0 / 2.0) * g4 * l9
+                    + q(477.0, 2.0) * g4 * l9
                     + 5904.0 * g2 * l1 * l9
                     - 1440.0 * l1.powi(2) * l9
                     + 3156.0 * g2 * l2 * l9
@@ -190,23 +207,23 @@ impl Model<7> for MainModel {
                     - 2432.0 * l7 * l9_2
                     - 5618.0 * l8 * l9_2
                     - 5760.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (3.0 / 2.0) * g4 - (327.0 / 4.0) * g2 * l9
+                b1: q(3.0, 2.0) * g4 - q(327.0, 4.0) * g2 * l9
                     + 16.0 * l1 * l9
                     + 20.0 * l2 * l9
                     + 16.0 * l6 * l9
                     + 16.0 * l7 * l9
                     + 64.0 * l8 * l9
                     + 136.0 * l9_2,
-                b2: (103.0 / 48.0) * g.powi(6)
+                b2: q(103.0, 48.0) * g.powi(6)
                     + 20.0 * g4 * l1
                     + 25.0 * g4 * l2
                     + 20.0 * g4 * l6
                     + 20.0 * g4 * l7
                     + 8.0 * g4 * l8
-                    - (301897.0 / 192.0) * g4 * l9
+                    - q(301897.0, 192.0) * g4 * l9
                     + 448.0 * g2 * l1 * l9
                     - 1760.0 * l1.powi(2) * l9
                     + 560.0 * g2 * l2 * l9
@@ -230,15 +247,190 @@ impl Model<7> for MainModel {
                     - 3776.0 * l7 * l9_2
                     - 7760.0 * l8 * l9_2
                     + 2414.0 * l9.powi(3),
-                b3: 0.,
+                b3: S::from_f64(0.),
             },
         ]
     }
 
-    fn stability_condition(&self, couplings: &Couplings<7>) -> FinalStabilityResult {
-        let [_g, l1, l2, l6, _l7, l8, l9] = couplings.couplings;
+    fn jacobian(&self, couplings: &Couplings<7, S>) -> Jacobian<7, S> {
+        exact_jacobian(self, couplings)
+    }
+
+    // The quartic couplings are known to two loops
+    fn loop_orders(&self) -> [LoopOrder; 7] {
+        let mut loop_orders = [LoopOrder::Two; 7];
+        loop_orders[0] = LoopOrder::Three;
+        loop_orders
+    }
+
+    fn stability_condition(&self, couplings: &Couplings<7, S>) -> FinalStabilityResult {
+        for (condition, [alpha, b0, b1, b2, c0, c1, c2]) in
+            STABILITY_CONDITIONS.iter().zip(stability_arguments(couplings.couplings))
+        {
+            match stab3vev(alpha, b0, b1, b2, c0, c1, c2) {
+                StabilityResult::Stable => {}
+                other if condition.allowed => return FinalStabilityResult::UnstableAllowed(other),
+                other => return FinalStabilityResult::UnstableDisallowed(other),
+            };
+        }
+
+        FinalStabilityResult::Stable
+    }
+
+    fn stability_conditions(&self) -> &'static [StabilityCondition] {
+        &STABILITY_CONDITIONS
+    }
+
+    fn violated_stability_conditions(&self, couplings: &Couplings<7, S>) -> Vec<StabilityViolation> {
+        STABILITY_CONDITIONS
+            .iter()
+            .zip(stability_arguments(couplings.couplings))
+            .enumerate()
+            .filter_map(|(index, (condition, [alpha, b0, b1, b2, c0, c1, c2]))| {
+                match stab3vev(alpha, b0, b1, b2, c0, c1, c2) {
+                    StabilityResult::Stable => None,
+                    result => Some(StabilityViolation {
+                        index,
+                        condition: *condition,
+                        result,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    fn breaking_patterns(&self) -> &'static [BreakingPattern] {
+        &BREAKING_PATTERNS
+    }
+
+    fn classify_vacuum(&self, violation: &StabilityViolation) -> Option<BreakingPattern> {
+        let subalgebra = Model::<7, S>::unbroken_subalgebra(self, violation, 1.)?;
+        let ([_chi, omega_bl, omega_r], _) = vacuum_direction(violation)?;
+        so10::classify(&BREAKING_PATTERNS, &subalgebra, omega_bl, omega_r)
+    }
+
+    fn daughter(&self, pattern: &BreakingPattern) -> Option<&'static dyn Threshold<7>> {
+        match *pattern {
+            so10::STANDARD_MODEL | so10::ALIGNED_STANDARD_MODEL => Some(&STANDARD_MODEL_DAUGHTER),
+            so10::SU5 => Some(&SU5_DAUGHTER),
+            so10::SU5_U1 | so10::FLIPPED_SU5_U1 => Some(&SU5_U1_DAUGHTER),
+            _ => None,
+        }
+    }
+
+    fn gauge_algebra(&self) -> Option<&'static GaugeAlgebra> {
+        Some(so10::adjoint_spinor_algebra())
+    }
+
+    fn vacuum_fields(&self, violation: &StabilityViolation) -> Option<Vec<Vec<f64>>> {
+        let ([chi, omega_bl, omega_r], state) = vacuum_direction(violation)?;
+        Some(vec![so10::adjoint_vev(omega_bl, omega_r), so10::spinor_vev(chi, state)])
+    }
+
+    fn mass_matrices(&self, couplings: &Couplings<7, S>, fields: &[Vec<f64>]) -> Option<MassMatrices> {
+        let [g, l1, l2, l6, l7, l8, l9] = couplings.couplings.map(S::to_f64);
+        let potential = so10::adjoint_spinor_potential([l1, l2, l6, l7, l8, l9]);
+        Some(MassMatrices {
+            vector: so10::adjoint_spinor_algebra().vector_mass_matrix(fields, g),
+            scalar: potential.hessian(&fields.concat()),
+            fermion: Matrix::zeros(0, 0),
+        })
+    }
+
+    fn certified_stability_condition(
+        &self,
+        couplings: &Couplings<7, S>,
+        precision: Precision,
+    ) -> Certified<Option<StabilityViolation>> {
+        match precision {
+            Precision::Double => certified_stability_condition::<f64, S>(couplings),
+            Precision::DoubleDouble => certified_stability_condition::<DoubleDouble, S>(couplings),
+        }
+    }
+}
 
-        match stab3vev(
+// Fock states of the 16 carrying the vev for each of the allowed STABILITY_CONDITIONS
+// Beta function of g, which depends on the quartics from three loops on
+fn gauge_beta_function<S: Scalar>(couplings: &Couplings<7, S>) -> BetaFunctionValue<S>
+where
+    f64: Mul<S, Output = S>,
+{
+    let q = S::rational;
+    let [g, l1, l2, l6, l7, l8, l9] = couplings.couplings;
+
+    let g3 = g.powi(3);
+    let g5 = g.powi(5);
+
+    BetaFunctionValue {
+        b1: q(-70.0, 3.0) * g3,
+        b2: q(-1757.0, 6.0) * g5,
+        b3: q(1077557.0, 1728.0) * g.powi(7) + 640.0 * g5 * l1 - 3008.0 * g3 * l1.powi(2)
+            + 272.0 * g5 * l2
+            - 3296.0 * g3 * l1 * l2
+            - 1532.0 * g3 * l2.powi(2)
+            + 180.0 * g5 * l6
+            - 1088.0 * g3 * l6.powi(2)
+            + 132.0 * g5 * l7
+            - 640.0 * g3 * l6 * l7
+            - 1280.0 * g3 * l7.powi(2)
+            + 256.0 * g5 * l8
+            - 3488.0 * g3 * l8.powi(2)
+            + 512.0 * g5 * l9
+            - 1744.0 * g3 * l8 * l9
+            - 6322.0 * g3 * l9.powi(2),
+    }
+}
+
+const SINGLET_STATES: [usize; 2] = [so10::FLIPPED_SINGLET, so10::SINGLET];
+
+// (chi, w_BL, w_R) along which an allowed stability condition is violated and the state of the 16
+// chi belongs to. The second vev of Violated2 only differs by the sign of w_R, which does not change
+// the pattern.
+fn vacuum_direction(violation: &StabilityViolation) -> Option<([f64; 3], usize)> {
+    if !violation.condition.allowed {
+        return None;
+    }
+    match violation.result {
+        StabilityResult::Violated1(vev) | StabilityResult::Violated2(vev, _) => Some((vev, SINGLET_STATES[violation.index])),
+        _ => None,
+    }
+}
+
+static STANDARD_MODEL_DAUGHTER: Daughter<7, 3, GaugeTheory<3>> =
+    Daughter { model: so10::STANDARD_MODEL_GAUGE_THEORY, matching: so10::gauge_matching };
+static SU5_DAUGHTER: Daughter<7, 1, GaugeTheory<1>> =
+    Daughter { model: so10::SU5_GAUGE_THEORY, matching: so10::gauge_matching };
+static SU5_U1_DAUGHTER: Daughter<7, 2, GaugeTheory<2>> =
+    Daughter { model: so10::SU5_U1_GAUGE_THEORY, matching: so10::gauge_matching };
+
+const BREAKING_PATTERNS: [BreakingPattern; 8] = [
+    so10::STANDARD_MODEL,
+    so10::ALIGNED_STANDARD_MODEL,
+    so10::SU5,
+    so10::LEFT_RIGHT,
+    so10::PATI_SALAM,
+    so10::SU5_U1,
+    so10::FLIPPED_SU5_U1,
+    so10::SM_U1,
+];
+
+const STABILITY_CONDITIONS: [StabilityCondition; 4] = [
+    StabilityCondition { name: "allowed, b1 = -12 l9", allowed: true },
+    StabilityCondition { name: "allowed, b1 = +12 l9", allowed: true },
+    StabilityCondition { name: "disallowed, b1 = -8 l9", allowed: false },
+    StabilityCondition { name: "disallowed, b1 = +8 l9", allowed: false },
+];
+
+// Arguments of stab3vev for each of the STABILITY_CONDITIONS
+fn stability_arguments<T>(couplings: [T; 7]) -> [[T; 7]; 4]
+where
+    T: Copy + Add<Output = T> + Div<f64, Output = T>,
+    f64: Mul<T, Output = T>,
+{
+    let [_g, l1, l2, l6, _l7, l8, l9] = couplings;
+
+    [
+        [
             4. * l6,
             12. * l8 + 9. * l9,
             -12. * l9,
@@ -246,12 +438,8 @@ impl Model<7> for MainModel {
             9. * l1 + (21. / 4.) * l2,
             12. * l1 + 9. * l2,
             4. * l1 + 2. * l2,
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableAllowed(other),
-        };
-
-        match stab3vev(
+        ],
+        [
             4. * l6,
             12. * l8 + 9. * l9,
             12. * l9,
@@ -259,12 +447,8 @@ impl Model<7> for MainModel {
             9. * l1 + (21. / 4.) * l2,
             12. * l1 + 9. * l2,
             4. * l1 + 2. * l2,
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableAllowed(other),
-        };
-
-        match stab3vev(
+        ],
+        [
             4. * l6,
             16. * l8 + 16. * l9,
             -8. * l9,
@@ -272,12 +456,8 @@ impl Model<7> for MainModel {
             16. * l1 + 10. * l2,
             8. * l1 + 6. * l2,
             l1 + l2 / 4.,
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableDisallowed(other),
-        };
-
-        match stab3vev(
+        ],
+        [
             4. * l6,
             16. * l8 + 16. * l9,
             8. * l9,
@@ -285,11 +465,29 @@ impl Model<7> for MainModel {
             16. * l1 + 10. * l2,
             8. * l1 + 6. * l2,
             l1 + l2 / 4.,
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableDisallowed(other),
-        };
+        ],
+    ]
+}
 
-        FinalStabilityResult::Stable
+fn certified_stability_condition<B: IntervalBound, S: Scalar>(
+    couplings: &Couplings<7, S>,
+) -> Certified<Option<StabilityViolation>> {
+    let couplings = couplings.couplings.map(Interval::<B>::enclose);
+    for (index, (condition, [alpha, b0, b1, b2, c0, c1, c2])) in
+        STABILITY_CONDITIONS.iter().zip(stability_arguments(couplings)).enumerate()
+    {
+        match stab3vev_certified(alpha, b0, b1, b2, c0, c1, c2) {
+            Certified::Decided(StabilityResult::Stable) => {}
+            Certified::Decided(result) => {
+                return Certified::Decided(Some(StabilityViolation {
+                    index,
+                    condition: *condition,
+                    result,
+                }));
+            }
+            Certified::Undecided => return Certified::Undecided,
+        };
     }
+
+    Certified::Decided(None)
 }
@@ -1,2 +1,4 @@
 pub mod toy_model;
-pub mod main_model;
\ No newline at end of file
+pub mod main_model;
+pub mod so10;
+pub mod gauge_theory;
@@ -0,0 +1,505 @@
+use crate::model::{BreakingPattern, Couplings};
+use crate::models::gauge_theory::GaugeTheory;
+use crate::util::effective_potential::QuarticPotential;
+use crate::util::lie_algebra::{GaugeAlgebra, Representation, UnbrokenSubalgebra};
+use crate::util::linear_algebra::Matrix;
+use std::sync::OnceLock;
+
+// Residual subgroups of SO(10) for a vev of the 45, <45> = diag(w_BL, w_BL, w_BL, w_R, w_R) x i sigma_2,
+// optionally combined with the SU(5) singlet vev of the 16
+pub const STANDARD_MODEL: BreakingPattern =
+    BreakingPattern { subgroup: "SU(3)xSU(2)xU(1)", dimension: 12, rank: 4, supergroup: false };
+pub const SU5: BreakingPattern = BreakingPattern { subgroup: "SU(5)", dimension: 24, rank: 4, supergroup: true };
+pub const LEFT_RIGHT: BreakingPattern =
+    BreakingPattern { subgroup: "SU(3)xSU(2)xSU(2)xU(1)", dimension: 15, rank: 5, supergroup: true };
+pub const PATI_SALAM: BreakingPattern =
+    BreakingPattern { subgroup: "SU(4)xSU(2)xU(1)", dimension: 19, rank: 5, supergroup: true };
+pub const SU5_U1: BreakingPattern = BreakingPattern { subgroup: "SU(5)xU(1)", dimension: 25, rank: 5, supergroup: true };
+pub const FLIPPED_SU5_U1: BreakingPattern =
+    BreakingPattern { subgroup: "flipped SU(5)xU(1)", dimension: 25, rank: 5, supergroup: true };
+pub const SM_U1: BreakingPattern =
+    BreakingPattern { subgroup: "SU(3)xSU(2)xU(1)xU(1)", dimension: 13, rank: 5, supergroup: true };
+// The Standard Model from the 16 together with w_BL = 0 or w_R = 0, which the vev-based classification
+// this one replaced counted as a supergroup breaking
+pub const ALIGNED_STANDARD_MODEL: BreakingPattern =
+    BreakingPattern { subgroup: "SU(3)xSU(2)xU(1), w_BL w_R = 0", supergroup: true, ..STANDARD_MODEL };
+
+// Vev components below this are taken as vanishing when telling the aligned Standard Model apart
+const VEV_EPSILON: f64 = 1E-12;
+
+const N: usize = 10;
+
+// Index pairs (a, b), a < b, labelling the generators T_ab = E_ab - E_ba of the vector representation
+fn generator_indices() -> Vec<(usize, usize)> {
+    (0..N).flat_map(|a| ((a + 1)..N).map(move |b| (a, b))).collect()
+}
+
+fn vector_generators() -> Vec<Matrix> {
+    generator_indices()
+        .into_iter()
+        .map(|(a, b)| {
+            let mut generator = Matrix::zeros(N, N);
+            generator[(a, b)] = 1.;
+            generator[(b, a)] = -1.;
+            generator
+        })
+        .collect()
+}
+
+// The 45 acting on the antisymmetric matrices sum_ab phi_ab T_ab by commutation
+fn adjoint_generators() -> Vec<Matrix> {
+    let vector = vector_generators();
+    let indices = generator_indices();
+    vector
+        .iter()
+        .map(|x| {
+            let mut generator = Matrix::zeros(indices.len(), indices.len());
+            for (column, y) in vector.iter().enumerate() {
+                let commutator = x.commutator(y);
+                for (row, &(a, b)) in indices.iter().enumerate() {
+                    generator[(row, column)] = commutator[(a, b)];
+                }
+            }
+            generator
+        })
+        .collect()
+}
+
+// Complex matrix as real and imaginary part
+type Complex = (Matrix, Matrix);
+
+fn complex_mul((a, b): &Complex, (c, d): &Complex) -> Complex {
+    let mut re = a.mul(c);
+    re.add_scaled(-1., &b.mul(d));
+    let mut im = a.mul(d);
+    im.add_scaled(1., &b.mul(c));
+    (re, im)
+}
+
+const MODES: usize = N / 2;
+const STATES: usize = 1 << MODES;
+
+// Gamma matrices on the states of five fermionic oscillators, Gamma_2k = b_k + b_k^dagger and
+// Gamma_2k+1 = i (b_k - b_k^dagger)
+fn gammas() -> Vec<Complex> {
+    let zero = Matrix::zeros(STATES, STATES);
+    (0..MODES)
+        .flat_map(|k| {
+            let mut sum = Matrix::zeros(STATES, STATES);
+            let mut difference = Matrix::zeros(STATES, STATES);
+            for state in (0..STATES).filter(|state| state & (1 << k) != 0) {
+                // Jordan-Wigner sign from the occupied modes before k
+                let sign = if (state & ((1 << k) - 1)).count_ones().is_multiple_of(2) { 1. } else { -1. };
+                let lowered = state ^ (1 << k);
+                sum[(lowered, state)] = sign;
+                sum[(state, lowered)] = sign;
+                difference[(lowered, state)] = sign;
+                difference[(state, lowered)] = -sign;
+            }
+            [(sum, zero.clone()), (zero.clone(), difference)]
+        })
+        .collect()
+}
+
+// The 16 consists of the states with an even occupation number
+fn even_states() -> Vec<usize> {
+    (0..STATES).filter(|state: &usize| state.count_ones().is_multiple_of(2)).collect()
+}
+
+// Complex matrix restricted to the 16, acting on (Re psi, Im psi) as [[re, -im], [im, re]]
+fn realify((re, im): &Complex) -> Matrix {
+    let even = even_states();
+    let dimension = even.len();
+    let mut result = Matrix::zeros(2 * dimension, 2 * dimension);
+    for (i, &row) in even.iter().enumerate() {
+        for (j, &column) in even.iter().enumerate() {
+            result[(i, j)] = re[(row, column)];
+            result[(i, j + dimension)] = -im[(row, column)];
+            result[(i + dimension, j)] = im[(row, column)];
+            result[(i + dimension, j + dimension)] = re[(row, column)];
+        }
+    }
+    result
+}
+
+// Sigma_ab = Gamma_a Gamma_b / 2 on the 16, the spinor counterparts of T_ab
+fn spinor_generators() -> Vec<Matrix> {
+    let gammas = gammas();
+    generator_indices()
+        .into_iter()
+        .map(|(a, b)| {
+            let mut generator = realify(&complex_mul(&gammas[a], &gammas[b]));
+            generator.scale(0.5);
+            generator
+        })
+        .collect()
+}
+
+// Gauge generators normalised to Tr(t_a t_b) = delta_ab in the 10, matching the beta function of g
+fn normalised(name: &'static str, generators: Vec<Matrix>) -> Representation {
+    let generators = generators
+        .into_iter()
+        .map(|mut generator| {
+            generator.scale(1. / 2_f64.sqrt());
+            generator
+        })
+        .collect();
+    Representation { name, generators }
+}
+
+pub fn adjoint_algebra() -> &'static GaugeAlgebra {
+    static ALGEBRA: OnceLock<GaugeAlgebra> = OnceLock::new();
+    ALGEBRA.get_or_init(|| GaugeAlgebra {
+        name: "SO(10)",
+        representations: vec![normalised("45", adjoint_generators())],
+    })
+}
+
+pub fn adjoint_spinor_algebra() -> &'static GaugeAlgebra {
+    static ALGEBRA: OnceLock<GaugeAlgebra> = OnceLock::new();
+    ALGEBRA.get_or_init(|| GaugeAlgebra {
+        name: "SO(10)",
+        representations: vec![normalised("45", adjoint_generators()), normalised("16", spinor_generators())],
+    })
+}
+
+// Components of diag(w_BL, w_BL, w_BL, w_R, w_R) x i sigma_2 in the basis of the adjoint
+pub fn adjoint_vev(omega_bl: f64, omega_r: f64) -> Vec<f64> {
+    generator_indices()
+        .into_iter()
+        .map(|(a, b)| match (a % 2, b == a + 1) {
+            (0, true) if a < 6 => omega_bl,
+            (0, true) => omega_r,
+            _ => 0.,
+        })
+        .collect()
+}
+
+// The SU(5) singlets of the 16 are pure spinors, Fock states with the modes of w_BL (the first
+// three) either all empty or all occupied. The oscillator vacuum is a singlet of the SU(5)
+// preserved by w_BL = w_R, the state with the two w_R modes occupied of the one preserved by
+// w_BL = -w_R.
+pub const SINGLET: usize = 0;
+pub const FLIPPED_SINGLET: usize = 0b11000;
+
+// Real components of chi times the given Fock state of the 16
+pub fn spinor_vev(chi: f64, state: usize) -> Vec<f64> {
+    let mut vev = vec![0.; SPINOR_DIMENSION];
+    let position = even_states().iter().position(|&s| s == state).expect("State is not part of the 16");
+    vev[position] = chi;
+    vev
+}
+
+const ADJOINT_DIMENSION: usize = N * (N - 1) / 2;
+const SPINOR_DIMENSION: usize = STATES;
+
+// Quadratic forms the potential of the 45 and 16 is built from, on the real fields (phi_ab, Re psi,
+// Im psi) where the 45 is sum_ab phi_ab T_ab and psi = (Re psi + i Im psi) / sqrt(2) is the 16:
+// tr(Phi^2), the entries (Phi^2)_ij for i <= j, |psi|^2, the components of Phi psi, and the real and
+// imaginary parts of psi^T C Gamma_a psi
+struct PotentialForms {
+    forms: Vec<Matrix>,
+    trace_2: usize,
+    square_entries: Vec<(usize, usize, usize)>,
+    spinor_norm: Option<usize>,
+    adjoint_spinor: Vec<usize>,
+    spinor_bilinears: Vec<usize>,
+}
+
+fn symmetric_form(dimension: usize, entries: impl IntoIterator<Item = (usize, usize, f64)>) -> Matrix {
+    let mut form = Matrix::zeros(dimension, dimension);
+    for (i, j, value) in entries {
+        form[(i, j)] += value / 2.;
+        form[(j, i)] += value / 2.;
+    }
+    form
+}
+
+fn potential_forms(with_spinor: bool) -> PotentialForms {
+    let dimension = ADJOINT_DIMENSION + if with_spinor { SPINOR_DIMENSION } else { 0 };
+    let indices = generator_indices();
+    let index = |a: usize, b: usize| indices.iter().position(|&pair| pair == (a.min(b), a.max(b))).unwrap();
+    // Phi_ab = sign * phi_index
+    let entry = |a: usize, b: usize| match a.cmp(&b) {
+        std::cmp::Ordering::Less => Some((index(a, b), 1.)),
+        std::cmp::Ordering::Greater => Some((index(a, b), -1.)),
+        std::cmp::Ordering::Equal => None,
+    };
+
+    let mut forms = vec![symmetric_form(dimension, (0..ADJOINT_DIMENSION).map(|i| (i, i, -2.)))];
+    let trace_2 = 0;
+
+    let mut square_entries = Vec::new();
+    for i in 0..N {
+        for j in i..N {
+            let form = symmetric_form(
+                dimension,
+                (0..N).filter_map(|k| {
+                    let (ik, s1) = entry(i, k)?;
+                    let (kj, s2) = entry(k, j)?;
+                    Some((ik, kj, s1 * s2))
+                }),
+            );
+            square_entries.push((forms.len(), i, j));
+            forms.push(form);
+        }
+    }
+
+    let mut spinor_norm = None;
+    let mut adjoint_spinor = Vec::new();
+    let mut spinor_bilinears = Vec::new();
+    if with_spinor {
+        let offset = ADJOINT_DIMENSION;
+        spinor_norm = Some(forms.len());
+        forms.push(symmetric_form(dimension, (0..SPINOR_DIMENSION).map(|i| (offset + i, offset + i, 1.))));
+
+        let generators = spinor_generators();
+        for m in 0..SPINOR_DIMENSION {
+            adjoint_spinor.push(forms.len());
+            forms.push(symmetric_form(
+                dimension,
+                generators.iter().enumerate().flat_map(|(ab, generator)| {
+                    (0..SPINOR_DIMENSION).map(move |n| (ab, offset + n, generator[(m, n)]))
+                }),
+            ));
+        }
+
+        // C = Gamma_1 Gamma_3 Gamma_5 Gamma_7 Gamma_9
+        let gammas = gammas();
+        let mut identity = Matrix::zeros(STATES, STATES);
+        for i in 0..STATES {
+            identity[(i, i)] = 1.;
+        }
+        let charge_conjugation = (1..N)
+            .step_by(2)
+            .fold((identity, Matrix::zeros(STATES, STATES)), |product, a| complex_mul(&product, &gammas[a]));
+        let even = even_states();
+        let (x, y) = (offset, offset + even.len());
+        for gamma in &gammas {
+            let (re, im) = complex_mul(&charge_conjugation, gamma);
+            let pairs = || even.iter().enumerate().flat_map(|(i, &row)| even.iter().enumerate().map(move |(j, &column)| (i, j, row, column)));
+            // psi^T (R + i I) psi = (x^T R x - y^T R y - x^T I y - y^T I x
+            //     + i (x^T R y + y^T R x + x^T I x - y^T I y)) / 2
+            spinor_bilinears.push(forms.len());
+            forms.push(symmetric_form(
+                dimension,
+                pairs().flat_map(|(i, j, row, column)| {
+                    let (r, m) = (re[(row, column)] / 2., im[(row, column)] / 2.);
+                    [(x + i, x + j, r), (y + i, y + j, -r), (x + i, y + j, -m), (y + i, x + j, -m)]
+                }),
+            ));
+            spinor_bilinears.push(forms.len());
+            forms.push(symmetric_form(
+                dimension,
+                pairs().flat_map(|(i, j, row, column)| {
+                    let (r, m) = (re[(row, column)] / 2., im[(row, column)] / 2.);
+                    [(x + i, y + j, r), (y + i, x + j, r), (x + i, x + j, m), (y + i, y + j, -m)]
+                }),
+            ));
+        }
+    }
+
+    PotentialForms {
+        forms,
+        trace_2,
+        square_entries,
+        spinor_norm,
+        adjoint_spinor,
+        spinor_bilinears,
+    }
+}
+
+fn cached_forms(with_spinor: bool) -> &'static PotentialForms {
+    static ADJOINT: OnceLock<PotentialForms> = OnceLock::new();
+    static ADJOINT_SPINOR: OnceLock<PotentialForms> = OnceLock::new();
+    let cache = if with_spinor { &ADJOINT_SPINOR } else { &ADJOINT };
+    cache.get_or_init(|| potential_forms(with_spinor))
+}
+
+fn adjoint_terms(forms: &PotentialForms, l1: f64, l2: f64) -> Vec<(f64, usize, usize)> {
+    let mut terms = vec![(l1 / 4. + 3. / 16. * l2, forms.trace_2, forms.trace_2)];
+    for &(form, i, j) in &forms.square_entries {
+        let multiplicity = if i == j { 1. } else { 2. };
+        terms.push((-l2 / 4. * multiplicity, form, form));
+    }
+    terms
+}
+
+// V = l1/4 tr(Phi^2)^2 + l2 (3/16 tr(Phi^2)^2 - 1/4 tr(Phi^4)), which reduces to the polynomial of
+// the allowed stability condition along adjoint_vev
+pub fn adjoint_potential(l1: f64, l2: f64) -> QuarticPotential<'static> {
+    let forms = cached_forms(false);
+    QuarticPotential {
+        forms: &forms.forms,
+        terms: adjoint_terms(forms, l1, l2),
+    }
+}
+
+// adjoint_potential + 4 l6 |s|^4 + l7 sum_a |psi^T C Gamma_a psi|^2 - 2 l8 |s|^2 tr(Phi^2) + 4 l9 |Phi s|^2,
+// with s = (Re psi, Im psi) and Phi acting on the 16. Along adjoint_vev and spinor_vev at the SINGLET
+// (FLIPPED_SINGLET) it reduces to the polynomial of the allowed stability condition with +12 l9
+// (-12 l9), the l7 term vanishing on pure spinors.
+pub fn adjoint_spinor_potential([l1, l2, l6, l7, l8, l9]: [f64; 6]) -> QuarticPotential<'static> {
+    let forms = cached_forms(true);
+    let spinor_norm = forms.spinor_norm.expect("Forms of the 16 are missing");
+    let mut terms = adjoint_terms(forms, l1, l2);
+    terms.push((4. * l6, spinor_norm, spinor_norm));
+    terms.extend(forms.spinor_bilinears.iter().map(|&form| (l7, form, form)));
+    terms.push((-2. * l8, spinor_norm, forms.trace_2));
+    terms.extend(forms.adjoint_spinor.iter().map(|&form| (4. * l9, form, form)));
+    QuarticPotential {
+        forms: &forms.forms,
+        terms,
+    }
+}
+
+// Pattern among the given ones, which may be model-specific variants of the ones here, with the
+// dimension and rank of the unbroken subalgebra. SU(5)xU(1) and
+// its flipped version are isomorphic and distinguished by the relative sign of w_BL and w_R, the
+// aligned Standard Model by a vanishing one of them.
+pub fn classify(
+    patterns: &[BreakingPattern],
+    subalgebra: &UnbrokenSubalgebra,
+    omega_bl: f64,
+    omega_r: f64,
+) -> Option<BreakingPattern> {
+    let mut candidates = patterns.iter().filter(|&&pattern| {
+        pattern != ALIGNED_STANDARD_MODEL
+            && pattern.dimension == subalgebra.dimension
+            && pattern.rank == subalgebra.rank
+    });
+    let first = *candidates.next()?;
+    match candidates.next() {
+        None if first == STANDARD_MODEL
+            && patterns.contains(&ALIGNED_STANDARD_MODEL)
+            && (omega_bl.abs() < VEV_EPSILON || omega_r.abs() < VEV_EPSILON) =>
+        {
+            Some(ALIGNED_STANDARD_MODEL)
+        }
+        None => Some(first),
+        Some(_) => {
+            let wanted = if omega_bl * omega_r > 0. { SU5_U1 } else { FLIPPED_SU5_U1 };
+            patterns.iter().find(|pattern| pattern.subgroup == wanted.subgroup).copied()
+        }
+    }
+}
+
+// Effective theories below the breaking scale with three generations of 16 fermions as the only light
+// matter. With Tr(t_a t_b) = delta in the 10 the SU(5) generators are normalised to 1/2 in the 5 and
+// Tr(X^2) = 2 over each 16, which puts hypercharge in the SU(5) normalisation.
+pub const STANDARD_MODEL_GAUGE_THEORY: GaugeTheory<3> = GaugeTheory { coefficients: [-7., -10. / 3., 4.] };
+pub const SU5_GAUGE_THEORY: GaugeTheory<1> = GaugeTheory { coefficients: [-43. / 3.] };
+pub const SU5_U1_GAUGE_THEORY: GaugeTheory<2> = GaugeTheory { coefficients: [-43. / 3., 4.] };
+
+// Tree-level matching of the unified gauge coupling, the first of the couplings of the parent theory,
+// to every gauge coupling of the daughter theory
+pub fn gauge_matching<const N: usize, const K: usize>(couplings: &Couplings<N>) -> Couplings<K> {
+    Couplings {
+        couplings: [couplings.couplings[0]; K],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unbroken(chi: f64, omega_bl: f64, omega_r: f64) -> (usize, usize) {
+        let subalgebra =
+            adjoint_spinor_algebra().unbroken_subalgebra(&[adjoint_vev(omega_bl, omega_r), spinor_vev(chi, SINGLET)], 1.);
+        (subalgebra.dimension, subalgebra.rank)
+    }
+
+    // Deterministic field configuration without special alignments
+    fn generic_fields(dimension: usize) -> Vec<f64> {
+        (0..dimension).map(|i| ((i + 1) as f64 * 0.618_033_988_749_895).fract() - 0.5).collect()
+    }
+
+    #[test]
+    fn test_potential_is_invariant() {
+        let potential = adjoint_spinor_potential([0.3, -0.2, 0.1, 0.7, 0.4, -0.6]);
+        let algebra = adjoint_spinor_algebra();
+        let phi = generic_fields(ADJOINT_DIMENSION + SPINOR_DIMENSION);
+        // Euler's theorem for the quartic potential
+        let gradient: Vec<f64> = potential.hessian(&phi).mul_vec(&phi).iter().map(|x| x / 3.).collect();
+        let (adjoint, spinor) = phi.split_at(ADJOINT_DIMENSION);
+        for a in 0..algebra.dimension() {
+            let mut rotated = algebra.representations[0].generators[a].mul_vec(adjoint);
+            rotated.extend(algebra.representations[1].generators[a].mul_vec(spinor));
+            let change: f64 = gradient.iter().zip(&rotated).map(|(x, y)| x * y).sum();
+            assert!(change.abs() < 1E-12, "Potential changes along generator {}: {}", a, change);
+        }
+        // The l7 invariant does not vanish identically
+        assert!(adjoint_spinor_potential([0., 0., 0., 1., 0., 0.]).value(&phi) > 1E-6);
+    }
+
+    #[test]
+    fn test_potential_reduces_to_stability_polynomial() {
+        let [l1, l2, l6, l7, l8, l9] = [0.3, -0.2, 0.1, 0.7, 0.4, -0.6];
+        let potential = adjoint_spinor_potential([l1, l2, l6, l7, l8, l9]);
+        let (chi, omega_bl, omega_r) = (0.8, -0.3, 0.5);
+        for (state, sign) in [(SINGLET, 1.), (FLIPPED_SINGLET, -1.)] {
+            let mut phi = adjoint_vev(omega_bl, omega_r);
+            phi.extend(spinor_vev(chi, state));
+            let expected = 4. * l6 * chi.powi(4)
+                + chi.powi(2)
+                    * ((12. * l8 + 9. * l9) * omega_bl.powi(2)
+                        + sign * 12. * l9 * omega_bl * omega_r
+                        + (8. * l8 + 4. * l9) * omega_r.powi(2))
+                + (9. * l1 + 21. / 4. * l2) * omega_bl.powi(4)
+                + (12. * l1 + 9. * l2) * omega_bl.powi(2) * omega_r.powi(2)
+                + (4. * l1 + 2. * l2) * omega_r.powi(4);
+            assert!((potential.value(&phi) - expected).abs() < 1E-12);
+        }
+    }
+
+    #[test]
+    fn test_unbroken_subgroups() {
+        assert_eq!(adjoint_spinor_algebra().rank(), 5);
+        let cases = [
+            ((0., 1., 0.), LEFT_RIGHT),
+            ((0., 0., 1.), PATI_SALAM),
+            ((0., 1., 1.), SU5_U1),
+            ((0., 1., -1.), FLIPPED_SU5_U1),
+            ((0., 1., 0.3), SM_U1),
+            ((1., 0., 0.), SU5),
+            ((1., 0.7, 0.7), SU5),
+            ((1., 1., 0.), STANDARD_MODEL),
+            ((1., 0.2, -0.5), STANDARD_MODEL),
+        ];
+        for ((chi, omega_bl, omega_r), pattern) in cases {
+            assert_eq!(unbroken(chi, omega_bl, omega_r), (pattern.dimension, pattern.rank), "{}", pattern.subgroup);
+        }
+    }
+
+    #[test]
+    fn test_classify_keeps_aligned_standard_model() {
+        let patterns = [STANDARD_MODEL, ALIGNED_STANDARD_MODEL, SU5, SU5_U1, FLIPPED_SU5_U1];
+        let classify_vev = |chi: f64, omega_bl: f64, omega_r: f64| {
+            let subalgebra = adjoint_spinor_algebra()
+                .unbroken_subalgebra(&[adjoint_vev(omega_bl, omega_r), spinor_vev(chi, SINGLET)], 1.);
+            classify(&patterns, &subalgebra, omega_bl, omega_r)
+        };
+        assert_eq!(classify_vev(1., 1., 0.), Some(ALIGNED_STANDARD_MODEL));
+        assert_eq!(classify_vev(1., 0., 1.), Some(ALIGNED_STANDARD_MODEL));
+        assert_eq!(classify_vev(1., 0.2, -0.5), Some(STANDARD_MODEL));
+        assert_eq!(classify_vev(0., 1., 1.), Some(SU5_U1));
+        // Without the aligned pattern among the given ones these are the Standard Model
+        let subalgebra =
+            adjoint_spinor_algebra().unbroken_subalgebra(&[adjoint_vev(1., 0.), spinor_vev(1., SINGLET)], 1.);
+        assert_eq!(classify(&[STANDARD_MODEL, SU5], &subalgebra, 1., 0.), Some(STANDARD_MODEL));
+    }
+
+    #[test]
+    fn test_classify_returns_given_patterns() {
+        let local_sm_u1 = BreakingPattern { supergroup: false, ..SM_U1 };
+        let local_flipped = BreakingPattern { supergroup: false, ..FLIPPED_SU5_U1 };
+        let patterns = [local_sm_u1, SU5_U1, local_flipped];
+        let classify_adjoint = |omega_bl: f64, omega_r: f64| {
+            let subalgebra = adjoint_algebra().unbroken_subalgebra(&[adjoint_vev(omega_bl, omega_r)], 1.);
+            classify(&patterns, &subalgebra, omega_bl, omega_r)
+        };
+        assert_eq!(classify_adjoint(1., 0.3), Some(local_sm_u1));
+        assert_eq!(classify_adjoint(1., 1.), Some(SU5_U1));
+        assert_eq!(classify_adjoint(1., -1.), Some(local_flipped));
+    }
+}
@@ -1,10 +1,42 @@
-use crate::model::{BetaFunctionValue, Couplings, Model};
-use crate::util::stability::{stab2vev, FinalStabilityResult, StabilityResult};
+use crate::model::{BetaFunctionValue, BreakingPattern, Couplings, Jacobian, LoopOrder, Model};
+use crate::models::gauge_theory::GaugeTheory;
+use crate::models::so10;
+use crate::multi_stage::{Daughter, Threshold};
+use crate::util::double_double::DoubleDouble;
+use crate::util::dual::exact_jacobian;
+use crate::util::interval::{Interval, IntervalBound, Precision};
+use crate::util::effective_potential::MassMatrices;
+use crate::util::lie_algebra::GaugeAlgebra;
+use crate::util::linear_algebra::Matrix;
+use crate::util::scalar::Scalar;
+use crate::util::stability::{
+    stab2vev, stab2vev_certified, Certified, FinalStabilityResult, StabilityCondition, StabilityResult,
+    StabilityViolation,
+};
+use std::ops::{Add, Div, Mul};
 
 #[derive(Debug, Clone)]
 pub struct ToyModel;
-impl Model<3> for ToyModel {
-    fn beta_function(&self, couplings: &Couplings<3>) -> [BetaFunctionValue; 3] {
+impl<S: Scalar> Model<3, S> for ToyModel
+where
+    f64: Mul<S, Output = S>,
+{
+    fn beta_function(&self, couplings: &Couplings<3, S>) -> [BetaFunctionValue<S>; 3] {
+        let mut beta_functions = self.dependent_beta_function(couplings);
+        beta_functions[0] = gauge_beta_function(couplings);
+        beta_functions
+    }
+
+    // The quartics enter the running of the gauge coupling only at three loops
+    fn independent_couplings(&self, loop_order: LoopOrder) -> &'static [usize] {
+        match loop_order {
+            LoopOrder::Three => &[],
+            LoopOrder::One | LoopOrder::Two => &[0],
+        }
+    }
+
+    fn dependent_beta_function(&self, couplings: &Couplings<3, S>) -> [BetaFunctionValue<S>; 3] {
+        let q = S::rational;
         let g = couplings.couplings[0];
         let l1 = couplings.couplings[1];
         let l2 = couplings.couplings[2];
@@ -21,17 +53,12 @@ impl Model<3> for ToyModel {
 
         [
             BetaFunctionValue {
-                b1: -24.0 * g.powi(3),
-                b2: (-697.0/2.0) * g.powi(5),
-                b3: (-291217.0 / 96.0) * g.powi(7)
-                    + 640.0 * g.powi(5) * l1
-                    - 3008.0 * g.powi(3) * l1_2
-                    + 272.0 * g.powi(5) * l2
-                    - 3296.0 * g.powi(3) * l1 * l2
-                    - 1532.0 * g.powi(3) * l2_2
+                b1: S::from_f64(0.),
+                b2: S::from_f64(0.),
+                b3: S::from_f64(0.),
             },
             BetaFunctionValue {
-                b1: (27./4.) * g4 - 96. * g2 * l1 + 424. * l1_2 + 412. * l1*l2 + (279./2.) * l2_2,
+                b1: q(27., 4.) * g4 - 96. * g2 * l1 + 424. * l1_2 + 412. * l1*l2 + q(279., 2.) * l2_2,
                 b2: 72.0 * g6
                     - 360.0 * g4 * l1
                     + 25600.0 * g2 * l1_2
@@ -42,7 +69,7 @@ impl Model<3> for ToyModel {
                     + 8172.0 * g2 * l2_2
                     - 21052.0 * l1 * l2_2
                     - 4572.0 * l2_3,
-                b3: 0.0
+                b3: S::from_f64(0.)
             },
             BetaFunctionValue {
                 b1: -3.0 * g4 - 96.0 * g2 * l2 + 96.0 * l1 * l2 - 4.0 * l2_2,
@@ -54,41 +81,187 @@ impl Model<3> for ToyModel {
                     - 304.0 * g2 * l2_2
                     - 16096.0 * l1 * l2_2
                     - 3660.0 * l2_3,
-                b3: 0.0
+                b3: S::from_f64(0.)
             },
         ]
     }
 
-    fn stability_condition(&self, couplings: &Couplings<3>) -> FinalStabilityResult {
-        let [_g, l1, l2] = couplings.couplings;
-
-        match stab2vev(
-            9. * l1 + 21.0 / 4.0 * l2,
-            12. * l1 + 9. * l2,
-            4. * l1 + 2. * l2
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableAllowed(other),
-        };
+    fn jacobian(&self, couplings: &Couplings<3, S>) -> Jacobian<3, S> {
+        exact_jacobian(self, couplings)
+    }
 
-        match stab2vev(
-            16. * l1 + 10. * l2,
-            8. * l1 + 6. * l2,
-            l1 + l2 / 4.
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableDisallowed(other),
-        };
+    // The quartic couplings are known to two loops
+    fn loop_orders(&self) -> [LoopOrder; 3] {
+        [LoopOrder::Three, LoopOrder::Two, LoopOrder::Two]
+    }
 
-        match stab2vev(
-            l1 + l2 / 4.,
-            2. * l1 + 3.0 / 2.0 * l2,
-            l1 + l2 / 4.
-        ) {
-            StabilityResult::Stable => {}
-            other => return FinalStabilityResult::UnstableDisallowed(other),
-        };
+    fn stability_condition(&self, couplings: &Couplings<3, S>) -> FinalStabilityResult {
+        for (condition, [a0, a1, a2]) in STABILITY_CONDITIONS.iter().zip(stability_arguments(couplings.couplings)) {
+            match stab2vev(a0, a1, a2) {
+                StabilityResult::Stable => {}
+                other if condition.allowed => return FinalStabilityResult::UnstableAllowed(other),
+                other => return FinalStabilityResult::UnstableDisallowed(other),
+            };
+        }
 
         FinalStabilityResult::Stable
     }
-}
\ No newline at end of file
+
+    fn stability_conditions(&self) -> &'static [StabilityCondition] {
+        &STABILITY_CONDITIONS
+    }
+
+    fn violated_stability_conditions(&self, couplings: &Couplings<3, S>) -> Vec<StabilityViolation> {
+        STABILITY_CONDITIONS
+            .iter()
+            .zip(stability_arguments(couplings.couplings))
+            .enumerate()
+            .filter_map(|(index, (condition, [a0, a1, a2]))| match stab2vev(a0, a1, a2) {
+                StabilityResult::Stable => None,
+                result => Some(StabilityViolation {
+                    index,
+                    condition: *condition,
+                    result,
+                }),
+            })
+            .collect()
+    }
+
+    fn breaking_patterns(&self) -> &'static [BreakingPattern] {
+        &BREAKING_PATTERNS
+    }
+
+    fn classify_vacuum(&self, violation: &StabilityViolation) -> Option<BreakingPattern> {
+        let subalgebra = Model::<3, S>::unbroken_subalgebra(self, violation, 1.)?;
+        let [omega_bl, omega_r] = vacuum_direction(violation)?;
+        so10::classify(&BREAKING_PATTERNS, &subalgebra, omega_bl, omega_r)
+    }
+
+    fn daughter(&self, pattern: &BreakingPattern) -> Option<&'static dyn Threshold<3>> {
+        // Only vacua of the 45 occur, and no effective theory below SM_U1 is implemented
+        match *pattern {
+            so10::SU5_U1 | so10::FLIPPED_SU5_U1 => Some(&SU5_U1_DAUGHTER),
+            _ => None,
+        }
+    }
+
+    fn gauge_algebra(&self) -> Option<&'static GaugeAlgebra> {
+        Some(so10::adjoint_algebra())
+    }
+
+    fn vacuum_fields(&self, violation: &StabilityViolation) -> Option<Vec<Vec<f64>>> {
+        let [omega_bl, omega_r] = vacuum_direction(violation)?;
+        Some(vec![so10::adjoint_vev(omega_bl, omega_r)])
+    }
+
+    fn mass_matrices(&self, couplings: &Couplings<3, S>, fields: &[Vec<f64>]) -> Option<MassMatrices> {
+        let [g, l1, l2] = couplings.couplings.map(S::to_f64);
+        Some(MassMatrices {
+            vector: so10::adjoint_algebra().vector_mass_matrix(fields, g),
+            scalar: so10::adjoint_potential(l1, l2).hessian(&fields.concat()),
+            fermion: Matrix::zeros(0, 0),
+        })
+    }
+
+    fn certified_stability_condition(
+        &self,
+        couplings: &Couplings<3, S>,
+        precision: Precision,
+    ) -> Certified<Option<StabilityViolation>> {
+        match precision {
+            Precision::Double => certified_stability_condition::<f64, S>(couplings),
+            Precision::DoubleDouble => certified_stability_condition::<DoubleDouble, S>(couplings),
+        }
+    }
+}
+
+// (w_BL, w_R) along which the allowed stability condition is violated
+// Beta function of g, which depends on the quartics from three loops on
+fn gauge_beta_function<S: Scalar>(couplings: &Couplings<3, S>) -> BetaFunctionValue<S>
+where
+    f64: Mul<S, Output = S>,
+{
+    let q = S::rational;
+    let [g, l1, l2] = couplings.couplings;
+
+    BetaFunctionValue {
+        b1: -24.0 * g.powi(3),
+        b2: q(-697.0, 2.0) * g.powi(5),
+        b3: q(-291217.0, 96.0) * g.powi(7)
+            + 640.0 * g.powi(5) * l1
+            - 3008.0 * g.powi(3) * l1.powi(2)
+            + 272.0 * g.powi(5) * l2
+            - 3296.0 * g.powi(3) * l1 * l2
+            - 1532.0 * g.powi(3) * l2.powi(2)
+    }
+}
+
+fn vacuum_direction(violation: &StabilityViolation) -> Option<[f64; 2]> {
+    if !violation.condition.allowed {
+        return None;
+    }
+    match violation.result {
+        StabilityResult::Violated1([omega_bl, omega_r, _]) | StabilityResult::Violated2([omega_bl, omega_r, _], _) => {
+            Some([omega_bl, omega_r])
+        }
+        _ => None,
+    }
+}
+
+// Without the 16 the generic vacuum of the 45 is the intended one
+const SM_U1: BreakingPattern = BreakingPattern { supergroup: false, ..so10::SM_U1 };
+
+static SU5_U1_DAUGHTER: Daughter<3, 2, GaugeTheory<2>> =
+    Daughter { model: so10::SU5_U1_GAUGE_THEORY, matching: so10::gauge_matching };
+
+const BREAKING_PATTERNS: [BreakingPattern; 5] = [
+    SM_U1,
+    so10::LEFT_RIGHT,
+    so10::PATI_SALAM,
+    so10::SU5_U1,
+    so10::FLIPPED_SU5_U1,
+];
+
+const STABILITY_CONDITIONS: [StabilityCondition; 3] = [
+    StabilityCondition { name: "allowed", allowed: true },
+    StabilityCondition { name: "disallowed, a1 = 8 l1 + 6 l2", allowed: false },
+    StabilityCondition { name: "disallowed, a1 = 2 l1 + 3/2 l2", allowed: false },
+];
+
+// Arguments of stab2vev for each of the STABILITY_CONDITIONS
+fn stability_arguments<T>(couplings: [T; 3]) -> [[T; 3]; 3]
+where
+    T: Copy + Add<Output = T> + Div<f64, Output = T>,
+    f64: Mul<T, Output = T>,
+{
+    let [_g, l1, l2] = couplings;
+
+    [
+        [9. * l1 + 21.0 / 4.0 * l2, 12. * l1 + 9. * l2, 4. * l1 + 2. * l2],
+        [16. * l1 + 10. * l2, 8. * l1 + 6. * l2, l1 + l2 / 4.],
+        [l1 + l2 / 4., 2. * l1 + 3.0 / 2.0 * l2, l1 + l2 / 4.],
+    ]
+}
+
+fn certified_stability_condition<B: IntervalBound, S: Scalar>(
+    couplings: &Couplings<3, S>,
+) -> Certified<Option<StabilityViolation>> {
+    let couplings = couplings.couplings.map(Interval::<B>::enclose);
+    for (index, (condition, [a0, a1, a2])) in
+        STABILITY_CONDITIONS.iter().zip(stability_arguments(couplings)).enumerate()
+    {
+        match stab2vev_certified(a0, a1, a2) {
+            Certified::Decided(StabilityResult::Stable) => {}
+            Certified::Decided(result) => {
+                return Certified::Decided(Some(StabilityViolation {
+                    index,
+                    condition: *condition,
+                    result,
+                }));
+            }
+            Certified::Undecided => return Certified::Undecided,
+        };
+    }
+
+    Certified::Decided(None)
+}
@@ -0,0 +1,169 @@
+use crate::model::{BreakingPattern, Couplings, Model};
+use crate::simulation::{Divergence, IntegrationParameters, IntegrationResult, Integrator};
+use crate::util::perturbativity::PerturbativityViolation;
+use crate::util::stability::FinalStabilityResult;
+
+// How a stage of the running ended
+#[derive(Debug, Clone)]
+pub enum StageOutcome {
+    Unbroken,
+    InitiallyUnstable,
+    PerturbativityViolated(PerturbativityViolation),
+    // The residual symmetry, if the vacuum could be classified
+    Broken(FinalStabilityResult, Option<BreakingPattern>),
+    Diverged(Divergence),
+    Invalid,
+}
+
+// Running of one theory between two thresholds. The couplings are those of the theory of the stage,
+// whose number differs between stages.
+#[derive(Debug, Clone)]
+pub struct Stage {
+    // Breaking pattern at the threshold the stage starts at, None for the first stage
+    pub pattern: Option<BreakingPattern>,
+    pub initial_scale: f64,
+    pub initial_couplings: Vec<f64>,
+    pub outcome: StageOutcome,
+    pub end_scale: f64,
+    pub end_couplings: Vec<f64>,
+    pub steps: usize,
+}
+
+pub struct MultiStageResult<const N: usize> {
+    // Result of the first stage, as returned by perform_full_integration
+    pub result: IntegrationResult<N>,
+    pub stages: Vec<Stage>,
+}
+
+// Effective theory below a breaking scale of a theory with N couplings
+pub trait Threshold<const N: usize>: Sync {
+    // Matches the couplings at the breaking scale params.initial_scale and runs the effective theory,
+    // and the effective theories below its own breaking scales, down to params.final_scale
+    fn run(&self, pattern: BreakingPattern, couplings: &Couplings<N>, params: IntegrationParameters) -> Vec<Stage>;
+}
+
+// Daughter theory with K couplings that are matched to the N couplings of the parent
+pub struct Daughter<const N: usize, const K: usize, M> {
+    pub model: M,
+    pub matching: fn(&Couplings<N>) -> Couplings<K>,
+}
+impl<const N: usize, const K: usize, M> Threshold<N> for Daughter<N, K, M>
+where
+    M: Model<K> + Clone + Sync + 'static,
+{
+    fn run(&self, pattern: BreakingPattern, couplings: &Couplings<N>, params: IntegrationParameters) -> Vec<Stage> {
+        let final_scale = params.final_scale;
+        let mut integrator = Integrator::new(params, Box::new(self.model.clone()), (self.matching)(couplings));
+        run_stages(&mut integrator, Some(pattern), final_scale).1
+    }
+}
+
+impl<const N: usize> Integrator<N> {
+    // Runs to params.final_scale and, if the theory breaks before, continues in the daughter theory of
+    // the breaking pattern with the same step size down to final_scale, through every further threshold
+    pub fn perform_multi_stage_integration(&mut self, final_scale: f64) -> MultiStageResult<N> {
+        let (result, stages) = run_stages(self, None, final_scale);
+        MultiStageResult { result, stages }
+    }
+}
+
+fn run_stages<const N: usize>(
+    integrator: &mut Integrator<N>,
+    pattern: Option<BreakingPattern>,
+    final_scale: f64,
+) -> (IntegrationResult<N>, Vec<Stage>) {
+    let initial_scale = integrator.time_step.log_scale;
+    let initial_couplings = integrator.time_step.couplings.couplings.to_vec();
+    let result = integrator.perform_full_integration();
+
+    let outcome = match &result {
+        IntegrationResult::Unbroken(_) => StageOutcome::Unbroken,
+        IntegrationResult::InitiallyUnstable => StageOutcome::InitiallyUnstable,
+        IntegrationResult::PerturbativityViolated(_, violation, _) => {
+            StageOutcome::PerturbativityViolated(violation.clone())
+        }
+        IntegrationResult::Broken { result: stability_result, violations, .. } => StageOutcome::Broken(
+            stability_result.clone(),
+            violations.first().and_then(|violation| integrator.model.classify_vacuum(violation)),
+        ),
+        IntegrationResult::Diverged(_, divergence) => StageOutcome::Diverged(divergence.clone()),
+        IntegrationResult::Invalid => StageOutcome::Invalid,
+    };
+
+    let step_size = integrator.step_size();
+    let end_scale = integrator.time_step.log_scale;
+    let mut stages = vec![Stage {
+        pattern,
+        initial_scale,
+        initial_couplings,
+        outcome: outcome.clone(),
+        end_scale,
+        end_couplings: integrator.time_step.couplings.couplings.to_vec(),
+        steps: ((end_scale - initial_scale) / step_size).round() as usize,
+    }];
+
+    let StageOutcome::Broken(FinalStabilityResult::UnstableAllowed(_), Some(pattern)) = outcome else {
+        return (result, stages);
+    };
+    let Some(daughter) = integrator.model.daughter(&pattern) else {
+        return (result, stages);
+    };
+    if (final_scale - end_scale) * step_size <= 0. {
+        return (result, stages);
+    }
+    let params = IntegrationParameters {
+        initial_scale: end_scale,
+        final_scale,
+        num_steps: ((final_scale - end_scale) / step_size).ceil() as usize,
+        ..integrator.params.clone()
+    };
+    stages.extend(daughter.run(pattern, &integrator.time_step.couplings, params));
+    (result, stages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::main_model::MainModel;
+    use crate::models::so10;
+    use crate::test_support::test_params;
+    use crate::util::constants::PI_4_2;
+
+    #[test]
+    fn test_daughter_runs_below_breaking_scale() {
+        let params = test_params();
+        let couplings = Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        };
+        let final_scale = 100_f64.ln();
+        let mut integrator = Integrator::new(params, Box::new(MainModel), couplings);
+        let MultiStageResult { result, stages } = integrator.perform_multi_stage_integration(final_scale);
+
+        let IntegrationResult::Broken { result: FinalStabilityResult::UnstableAllowed(_), end_point, .. } = result else {
+            panic!("Benchmark point is expected to break");
+        };
+        let [parent, daughter] = &stages[..] else {
+            panic!("Expected two stages, got {:?}", stages);
+        };
+        let StageOutcome::Broken(_, Some(pattern)) = parent.outcome else {
+            panic!("Vacuum is expected to be classified");
+        };
+        assert_eq!(daughter.pattern, Some(pattern));
+        assert_eq!(daughter.initial_scale, end_point.log_scale);
+        assert!(matches!(daughter.outcome, StageOutcome::Unbroken));
+        assert!((daughter.end_scale - final_scale).abs() < 1E-6);
+
+        // One-loop running 1/g^2(t) = 1/g^2(t0) - 2 b (t - t0) / (16 pi^2)
+        let coefficients = match pattern {
+            so10::STANDARD_MODEL | so10::ALIGNED_STANDARD_MODEL => so10::STANDARD_MODEL_GAUGE_THEORY.coefficients.to_vec(),
+            so10::SU5 => so10::SU5_GAUGE_THEORY.coefficients.to_vec(),
+            _ => so10::SU5_U1_GAUGE_THEORY.coefficients.to_vec(),
+        };
+        let g = end_point.couplings.couplings[0];
+        for (&b, (&initial, &end)) in coefficients.iter().zip(daughter.initial_couplings.iter().zip(&daughter.end_couplings)) {
+            assert_eq!(initial, g);
+            let expected = (1. / (g * g) - 2. * b * (daughter.end_scale - daughter.initial_scale) / PI_4_2).powf(-0.5);
+            assert!((end - expected).abs() < 1E-4 * expected, "{} vs {}", end, expected);
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::model::{Couplings, Model, TimeStep};
+use crate::scanner::scanner::CouplingRanges;
+use crate::simulation::{IntegrationParameters, IntegrationResult, Integrator};
+use crate::util::random::standard_normal;
+use crate::util::stability::FinalStabilityResult;
+use std::thread;
+
+// Size of the initial Nelder-Mead simplex and of the first gradient step, and the initial CMA-ES
+// step size, in coordinates where every range is [0, 1]
+const INITIAL_STEP: f64 = 0.1;
+const INITIAL_SIGMA: f64 = 0.3;
+// Gradient steps end below this length in unit coordinates
+const MIN_GRADIENT_STEP: f64 = 1E-6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extremum {
+    Highest,
+    Lowest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimisationMethod {
+    NelderMead,
+    CmaEs,
+    // Projected steepest ascent with the sensitivity of the breaking scale, with step halving
+    Gradient,
+}
+
+#[derive(Debug, Clone)]
+pub struct OptimisationParameters<const N: usize> {
+    // Couplings with min == max are fixed
+    pub ranges: CouplingRanges<N>,
+    pub extremum: Extremum,
+    pub method: OptimisationMethod,
+    pub num_threads: usize,
+    // Starts from random points of the ranges
+    pub starts_per_thread: usize,
+    pub max_evaluations: usize,
+    // A start ends once the objective varies by less than this across the simplex or population
+    pub tolerance: f64,
+    // Objective of points without an allowed breaking, worse than every breaking scale
+    pub penalty: f64,
+    // Couplings along the running of each optimum, see Integrator::trajectory
+    pub trajectory_samples: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Optimum<const N: usize> {
+    pub couplings: Couplings<N>,
+    pub breaking_scale: f64,
+    pub evaluations: usize,
+    // From params.initial_scale to the breaking
+    pub trajectory: Vec<TimeStep<N>>,
+}
+
+// The best allowed breaking found by every start that found one at all, the most extremal first
+pub fn find_extremal_breaking_scale<const N: usize, M: Model<N> + Clone + Send + 'static>(
+    params: &IntegrationParameters,
+    model: &M,
+    optimisation: &OptimisationParameters<N>,
+) -> Vec<Optimum<N>> {
+    let mut params = params.clone();
+    params.sensitivity = optimisation.method == OptimisationMethod::Gradient;
+
+    let mut optima: Vec<Optimum<N>> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..optimisation.num_threads)
+            .map(|_| {
+                let params = params.clone();
+                let model = model.clone();
+                scope.spawn(move || {
+                    let mut objective = Objective::new(params, Box::new(model), optimisation);
+                    (0..optimisation.starts_per_thread).filter_map(|_| objective.optimise()).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    });
+
+    let sign = sign(optimisation.extremum);
+    optima.sort_by(|a, b| (sign * a.breaking_scale).total_cmp(&(sign * b.breaking_scale)));
+    optima
+}
+
+// The objective is minimised
+fn sign(extremum: Extremum) -> f64 {
+    match extremum {
+        Extremum::Highest => -1.,
+        Extremum::Lowest => 1.,
+    }
+}
+
+struct Objective<'a, const N: usize> {
+    integrator: Integrator<N>,
+    optimisation: &'a OptimisationParameters<N>,
+    // Couplings that are varied
+    free: Vec<usize>,
+    evaluations: usize,
+    // Best allowed breaking of the current start, with the scale where the integration stopped
+    best: Option<(Couplings<N>, f64, f64)>,
+}
+impl<'a, const N: usize> Objective<'a, N> {
+    fn new(params: IntegrationParameters, model: Box<dyn Model<N>>, optimisation: &'a OptimisationParameters<N>) -> Self {
+        let free = (0..N).filter(|&i| optimisation.ranges[i].0 != optimisation.ranges[i].1).collect();
+        let initial = Couplings { couplings: optimisation.ranges.map(|(min, _)| min) };
+        Self {
+            integrator: Integrator::new(params, model, initial),
+            optimisation,
+            free,
+            evaluations: 0,
+            best: None,
+        }
+    }
+
+    // Couplings at unit coordinates of the free couplings, clamped to the ranges
+    fn couplings(&self, u: &[f64]) -> Couplings<N> {
+        let mut couplings = self.optimisation.ranges.map(|(min, _)| min);
+        for (&i, &x) in self.free.iter().zip(u) {
+            let (min, max) = self.optimisation.ranges[i];
+            couplings[i] = min + x.clamp(0., 1.) * (max - min);
+        }
+        Couplings { couplings }
+    }
+
+    // Objective and, if the sensitivity is enabled, its gradient in unit coordinates
+    fn evaluate_with_gradient(&mut self, u: &[f64]) -> (f64, Option<Vec<f64>>) {
+        let couplings = self.couplings(u);
+        self.integrator.reset(&couplings);
+        self.evaluations += 1;
+        let IntegrationResult::Broken {
+            log_scale: scale,
+            result: FinalStabilityResult::UnstableAllowed(_),
+            end_point,
+            ..
+        } = self.integrator.perform_full_integration()
+        else {
+            return (self.optimisation.penalty, None);
+        };
+
+        let sign = sign(self.optimisation.extremum);
+        if self.best.as_ref().is_none_or(|(_, best, _)| sign * scale < sign * best) {
+            self.best = Some((couplings, scale, end_point.log_scale));
+        }
+        let gradient = end_point.sensitivity.and_then(|s| s.breaking_scale).map(|gradient| {
+            self.free
+                .iter()
+                .map(|&i| sign * gradient[i] * (self.optimisation.ranges[i].1 - self.optimisation.ranges[i].0))
+                .collect()
+        });
+        (sign * scale, gradient)
+    }
+
+    fn evaluate(&mut self, u: &[f64]) -> f64 {
+        self.evaluate_with_gradient(u).0
+    }
+
+    fn optimise(&mut self) -> Option<Optimum<N>> {
+        self.evaluations = 0;
+        self.best = None;
+        let start: Vec<f64> = self.free.iter().map(|_| rand::random::<f64>()).collect();
+        match self.optimisation.method {
+            _ if start.is_empty() => {
+                self.evaluate(&start);
+            }
+            OptimisationMethod::NelderMead => self.nelder_mead(start),
+            OptimisationMethod::CmaEs => self.cma_es(start),
+            OptimisationMethod::Gradient => self.gradient_descent(start),
+        }
+
+        let (couplings, breaking_scale, end_scale) = self.best.take()?;
+        self.integrator.reset(&couplings);
+        let trajectory = self
+            .integrator
+            .trajectory(end_scale, self.optimisation.trajectory_samples)
+            .unwrap_or_default();
+        Some(Optimum {
+            couplings,
+            breaking_scale,
+            evaluations: self.evaluations,
+            trajectory,
+        })
+    }
+
+    fn nelder_mead(&mut self, start: Vec<f64>) {
+        let d = start.len();
+        let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(d + 1);
+        for k in 0..=d {
+            let mut vertex = start.clone();
+            if k > 0 {
+                // Towards the inside of the ranges
+                vertex[k - 1] += if vertex[k - 1] > 0.5 { -INITIAL_STEP } else { INITIAL_STEP };
+            }
+            let value = self.evaluate(&vertex);
+            simplex.push((vertex, value));
+        }
+
+        while self.evaluations < self.optimisation.max_evaluations {
+            simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+            if simplex[d].1 - simplex[0].1 < self.optimisation.tolerance {
+                break;
+            }
+            let centroid: Vec<f64> = (0..d).map(|i| simplex[..d].iter().map(|(v, _)| v[i]).sum::<f64>() / d as f64).collect();
+            let point = |t: f64| -> Vec<f64> {
+                centroid.iter().zip(&simplex[d].0).map(|(c, w)| (c + t * (c - w)).clamp(0., 1.)).collect()
+            };
+
+            let reflected = point(1.);
+            let reflected_value = self.evaluate(&reflected);
+            if reflected_value < simplex[0].1 {
+                let expanded = point(2.);
+                let expanded_value = self.evaluate(&expanded);
+                simplex[d] = if expanded_value < reflected_value {
+                    (expanded, expanded_value)
+                } else {
+                    (reflected, reflected_value)
+                };
+            } else if reflected_value < simplex[d - 1].1 {
+                simplex[d] = (reflected, reflected_value);
+            } else {
+                let contracted = if reflected_value < simplex[d].1 { point(0.5) } else { point(-0.5) };
+                let contracted_value = self.evaluate(&contracted);
+                if contracted_value < reflected_value.min(simplex[d].1) {
+                    simplex[d] = (contracted, contracted_value);
+                } else {
+                    // Shrink towards the best vertex
+                    let best = simplex[0].0.clone();
+                    for (vertex, value) in simplex.iter_mut().skip(1) {
+                        *vertex = vertex.iter().zip(&best).map(|(x, b)| b + 0.5 * (x - b)).collect();
+                        *value = self.evaluate(vertex);
+                    }
+                }
+            }
+        }
+    }
+
+    fn cma_es(&mut self, start: Vec<f64>) {
+        let d = start.len();
+        let n = d as f64;
+        let lambda = 4 + (3. * n.ln()).floor() as usize;
+        let mu = lambda / 2;
+        let raw_weights: Vec<f64> = (1..=mu).map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln()).collect();
+        let total: f64 = raw_weights.iter().sum();
+        let weights: Vec<f64> = raw_weights.iter().map(|w| w / total).collect();
+        let mu_eff = 1. / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let c_sigma = (mu_eff + 2.) / (n + mu_eff + 5.);
+        let d_sigma = 1. + 2. * (((mu_eff - 1.) / (n + 1.)).sqrt() - 1.).max(0.) + c_sigma;
+        let c_c = (4. + mu_eff / n) / (n + 4. + 2. * mu_eff / n);
+        let c_1 = 2. / ((n + 1.3).powi(2) + mu_eff);
+        let c_mu = (1. - c_1).min(2. * (mu_eff - 2. + 1. / mu_eff) / ((n + 2.).powi(2) + mu_eff));
+        let chi_n = n.sqrt() * (1. - 1. / (4. * n) + 1. / (21. * n * n));
+
+        let mut mean = start;
+        let mut sigma = INITIAL_SIGMA;
+        let mut covariance: Vec<Vec<f64>> = (0..d).map(|i| (0..d).map(|j| if i == j { 1. } else { 0. }).collect()).collect();
+        let (mut p_sigma, mut p_c) = (vec![0.; d], vec![0.; d]);
+        let mut generation = 0;
+
+        while self.evaluations + lambda <= self.optimisation.max_evaluations {
+            let Some(cholesky) = cholesky(&covariance) else {
+                break;
+            };
+            // (z, y = L z, objective of mean + sigma y)
+            let mut population: Vec<(Vec<f64>, Vec<f64>, f64)> = (0..lambda)
+                .map(|_| {
+                    let z: Vec<f64> = (0..d).map(|_| standard_normal()).collect();
+                    let y: Vec<f64> = (0..d).map(|i| (0..=i).map(|j| cholesky[i][j] * z[j]).sum()).collect();
+                    let x: Vec<f64> = (0..d).map(|i| mean[i] + sigma * y[i]).collect();
+                    let value = self.evaluate(&x);
+                    (z, y, value)
+                })
+                .collect();
+            population.sort_by(|a, b| a.2.total_cmp(&b.2));
+            if population[lambda - 1].2 - population[0].2 < self.optimisation.tolerance {
+                break;
+            }
+
+            let weighted = |k: usize| -> Vec<f64> {
+                (0..d).map(|i| weights.iter().zip(&population).map(|(w, p)| w * if k == 0 { p.0[i] } else { p.1[i] }).sum()).collect()
+            };
+            let (z_w, y_w) = (weighted(0), weighted(1));
+            for i in 0..d {
+                mean[i] += sigma * y_w[i];
+            }
+
+            // The Cholesky factor takes the place of C^1/2
+            for i in 0..d {
+                p_sigma[i] = (1. - c_sigma) * p_sigma[i] + (c_sigma * (2. - c_sigma) * mu_eff).sqrt() * z_w[i];
+            }
+            let norm = p_sigma.iter().map(|p| p * p).sum::<f64>().sqrt();
+            generation += 1;
+            let threshold = (1.4 + 2. / (n + 1.)) * chi_n * (1. - (1. - c_sigma).powi(2 * generation)).sqrt();
+            let h_sigma = if norm < threshold { 1. } else { 0. };
+            for i in 0..d {
+                p_c[i] = (1. - c_c) * p_c[i] + h_sigma * (c_c * (2. - c_c) * mu_eff).sqrt() * y_w[i];
+            }
+            for i in 0..d {
+                for j in 0..d {
+                    let rank_mu: f64 = weights.iter().zip(&population).map(|(w, p)| w * p.1[i] * p.1[j]).sum();
+                    covariance[i][j] = (1. - c_1 - c_mu) * covariance[i][j]
+                        + c_1 * (p_c[i] * p_c[j] + (1. - h_sigma) * c_c * (2. - c_c) * covariance[i][j])
+                        + c_mu * rank_mu;
+                }
+            }
+            sigma *= (c_sigma / d_sigma * (norm / chi_n - 1.)).exp();
+        }
+    }
+
+    fn gradient_descent(&mut self, start: Vec<f64>) {
+        let mut position = start;
+        let (mut value, mut gradient) = self.evaluate_with_gradient(&position);
+        let mut step = INITIAL_STEP;
+        while step > MIN_GRADIENT_STEP && self.evaluations < self.optimisation.max_evaluations {
+            let Some(direction) = &gradient else {
+                return;
+            };
+            let norm = direction.iter().map(|g| g * g).sum::<f64>().sqrt();
+            if norm == 0. {
+                return;
+            }
+            let candidate: Vec<f64> = position.iter().zip(direction).map(|(x, g)| (x - step * g / norm).clamp(0., 1.)).collect();
+            let (candidate_value, candidate_gradient) = self.evaluate_with_gradient(&candidate);
+            if candidate_value < value && candidate_gradient.is_some() {
+                let improvement = value - candidate_value;
+                (position, value, gradient) = (candidate, candidate_value, candidate_gradient);
+                if improvement < self.optimisation.tolerance {
+                    return;
+                }
+                step *= 2.;
+            } else {
+                step /= 2.;
+            }
+        }
+    }
+}
+
+// Lower triangular L with L L^T = matrix, None unless positive definite
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let d = matrix.len();
+    let mut factor = vec![vec![0.; d]; d];
+    for i in 0..d {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| factor[i][k] * factor[j][k]).sum();
+            if i == j {
+                let diagonal = matrix[i][i] - sum;
+                if !diagonal.is_finite() || diagonal <= 0. {
+                    return None;
+                }
+                factor[i][i] = diagonal.sqrt();
+            } else {
+                factor[i][j] = (matrix[i][j] - sum) / factor[j][j];
+            }
+        }
+    }
+    Some(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{stub_params, LINEAR_RUNNING};
+
+    #[test]
+    fn test_extremal_breaking_scales() {
+        let params = stub_params();
+        // The breaking scale lambda_1 / lambda_2 below t0 is extremal in opposite corners
+        for (extremum, corner) in [(Extremum::Highest, [0.1, 0.5]), (Extremum::Lowest, [1., 0.2])] {
+            for method in [OptimisationMethod::NelderMead, OptimisationMethod::CmaEs, OptimisationMethod::Gradient] {
+                let optimisation = OptimisationParameters {
+                    ranges: [(0.1, 1.), (0.2, 0.5)],
+                    extremum,
+                    method,
+                    num_threads: 2,
+                    starts_per_thread: 2,
+                    max_evaluations: 500,
+                    tolerance: 1E-4,
+                    penalty: 100.,
+                    trajectory_samples: 10,
+                };
+                let optima = find_extremal_breaking_scale(&params, &LINEAR_RUNNING, &optimisation);
+                let best = &optima[0];
+                let [l1, l2] = best.couplings.couplings;
+                let expected = 10. - corner[0] / corner[1];
+                assert!((best.breaking_scale + 0.25 - expected).abs() < 0.05, "{:?} {:?}", method, best);
+                assert!((l1 / l2 - corner[0] / corner[1]).abs() < 0.05);
+                assert_eq!(best.trajectory.len(), 11);
+                assert!(best.trajectory[10].couplings.couplings[0].abs() < 0.05);
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::model::{Couplings, Model};
+use crate::simulation::{IntegrationParameters, IntegrationResult, Integrator, Outcome};
+use crate::util::random::standard_normal;
+use crate::util::stability::FinalStabilityResult;
+use std::fmt;
+use std::thread;
+
+const OUTCOMES: [Outcome; 7] = [
+    Outcome::Unbroken,
+    Outcome::InitiallyUnstable,
+    Outcome::PerturbativityViolated,
+    Outcome::BrokenAllowed,
+    Outcome::BrokenDisallowed,
+    Outcome::Diverged,
+    Outcome::Invalid,
+];
+const QUANTILES: [f64; 5] = [0.05, 0.25, 0.5, 0.75, 0.95];
+
+#[derive(Debug, Clone)]
+pub enum Distribution<const N: usize> {
+    // Independent normal distributions with these standard deviations
+    Gaussian([f64; N]),
+    // Uniform within these half widths
+    Box([f64; N]),
+}
+impl<const N: usize> Distribution<N> {
+    pub fn sample(&self, centre: &Couplings<N>) -> Couplings<N> {
+        let couplings = std::array::from_fn(|i| match self {
+            Distribution::Gaussian(widths) => centre.couplings[i] + widths[i] * standard_normal(),
+            Distribution::Box(widths) => centre.couplings[i] + widths[i] * (2. * rand::random::<f64>() - 1.),
+        });
+        Couplings { couplings }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RobustnessParameters<const N: usize> {
+    pub centre: Couplings<N>,
+    pub distribution: Distribution<N>,
+    pub num_threads: usize,
+    pub samples_per_thread: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScaleSummary {
+    pub count: usize,
+    pub mean: f64,
+    pub standard_deviation: f64,
+    // (probability, quantile)
+    pub quantiles: Vec<(f64, f64)>,
+}
+impl ScaleSummary {
+    fn new(mut scales: Vec<f64>) -> Option<Self> {
+        if scales.is_empty() {
+            return None;
+        }
+        scales.sort_by(f64::total_cmp);
+        let count = scales.len();
+        let mean = scales.iter().sum::<f64>() / count as f64;
+        let variance = scales.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / count as f64;
+        let quantiles = QUANTILES
+            .iter()
+            .map(|&p| {
+                // Linear interpolation between the order statistics
+                let position = p * (count - 1) as f64;
+                let (below, fraction) = (position.floor() as usize, position.fract());
+                let above = (below + 1).min(count - 1);
+                (p, scales[below] + fraction * (scales[above] - scales[below]))
+            })
+            .collect();
+        Some(Self {
+            count,
+            mean,
+            standard_deviation: variance.sqrt(),
+            quantiles,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RobustnessReport {
+    pub samples: usize,
+    // Number of samples with every outcome, in the order of OUTCOMES
+    pub outcomes: Vec<(Outcome, usize)>,
+    pub allowed_breaking_scale: Option<ScaleSummary>,
+    pub breaking_scale: Option<ScaleSummary>,
+    // Residual group of every breaking, "unclassified" when the vacuum could not be classified,
+    // most frequent first
+    pub patterns: Vec<(&'static str, usize)>,
+}
+impl RobustnessReport {
+    pub fn probability(&self, outcome: Outcome) -> f64 {
+        let count = self.outcomes.iter().find(|(o, _)| *o == outcome).map_or(0, |(_, count)| *count);
+        count as f64 / self.samples as f64
+    }
+}
+impl fmt::Display for RobustnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Samples: {}", self.samples)?;
+        writeln!(f, "Outcomes:")?;
+        for &(outcome, count) in &self.outcomes {
+            writeln!(f, "  {:<24} {:>8} {:>8.4}", outcome.name(), count, self.probability(outcome))?;
+        }
+        for (name, summary) in [("Allowed breaking scale", &self.allowed_breaking_scale), ("Breaking scale", &self.breaking_scale)] {
+            let Some(summary) = summary else {
+                continue;
+            };
+            writeln!(f, "{} ({} samples): mean {:.4}, standard deviation {:.4}", name, summary.count, summary.mean, summary.standard_deviation)?;
+            for (p, quantile) in &summary.quantiles {
+                writeln!(f, "  {:>4.0}%: {:.4}", 100. * p, quantile)?;
+            }
+        }
+        writeln!(f, "Breaking patterns:")?;
+        for (subgroup, count) in &self.patterns {
+            writeln!(f, "  {:<24} {:>8}", subgroup, count)?;
+        }
+        Ok(())
+    }
+}
+
+// Per thread samples
+#[derive(Default)]
+struct Samples {
+    outcomes: [usize; OUTCOMES.len()],
+    allowed_scales: Vec<f64>,
+    scales: Vec<f64>,
+    patterns: Vec<(&'static str, usize)>,
+}
+impl Samples {
+    fn add_pattern(&mut self, subgroup: &'static str, count: usize) {
+        match self.patterns.iter_mut().find(|(s, _)| *s == subgroup) {
+            Some((_, total)) => *total += count,
+            None => self.patterns.push((subgroup, count)),
+        }
+    }
+
+    fn merge(&mut self, other: Samples) {
+        for (total, count) in self.outcomes.iter_mut().zip(other.outcomes) {
+            *total += count;
+        }
+        self.allowed_scales.extend(other.allowed_scales);
+        self.scales.extend(other.scales);
+        for (subgroup, count) in other.patterns {
+            self.add_pattern(subgroup, count);
+        }
+    }
+}
+
+// Integrates samples of the distribution around the centre
+pub fn analyse_robustness<const N: usize, M: Model<N> + Clone + Send + 'static>(
+    params: &IntegrationParameters,
+    model: &M,
+    robustness: &RobustnessParameters<N>,
+) -> RobustnessReport {
+    let samples = thread::scope(|scope| {
+        let handles: Vec<_> = (0..robustness.num_threads)
+            .map(|_| {
+                let (params, model) = (params.clone(), model.clone());
+                scope.spawn(move || {
+                    let mut integrator = Integrator::new(params, Box::new(model), robustness.centre.clone());
+                    let mut samples = Samples::default();
+                    for _ in 0..robustness.samples_per_thread {
+                        integrator.reset(&robustness.distribution.sample(&robustness.centre));
+                        let result = integrator.perform_full_integration();
+                        let outcome = result.outcome();
+                        samples.outcomes[OUTCOMES.iter().position(|o| *o == outcome).unwrap()] += 1;
+                        if let IntegrationResult::Broken { log_scale: scale, result: stability_result, violations, .. } = result {
+                            if let FinalStabilityResult::UnstableAllowed(_) = stability_result {
+                                samples.allowed_scales.push(scale);
+                            }
+                            samples.scales.push(scale);
+                            let pattern = violations.first().and_then(|violation| integrator.model.classify_vacuum(violation));
+                            samples.add_pattern(pattern.map_or("unclassified", |p| p.subgroup), 1);
+                        }
+                    }
+                    samples
+                })
+            })
+            .collect();
+        let mut samples = Samples::default();
+        for handle in handles {
+            samples.merge(handle.join().unwrap());
+        }
+        samples
+    });
+
+    let mut patterns = samples.patterns;
+    patterns.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    RobustnessReport {
+        samples: robustness.num_threads * robustness.samples_per_thread,
+        outcomes: OUTCOMES.iter().copied().zip(samples.outcomes).collect(),
+        allowed_breaking_scale: ScaleSummary::new(samples.allowed_scales),
+        breaking_scale: ScaleSummary::new(samples.scales),
+        patterns,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::main_model::MainModel;
+    use crate::test_support::test_params;
+
+    #[test]
+    fn test_robustness_report() {
+        let params = test_params();
+        let centre = Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        };
+        let mut robustness = RobustnessParameters {
+            centre: centre.clone(),
+            distribution: Distribution::Box([0.; 7]),
+            num_threads: 2,
+            samples_per_thread: 2,
+        };
+
+        // Without spread every sample is the benchmark point
+        let report = analyse_robustness(&params, &MainModel, &robustness);
+        let mut integrator = Integrator::new(params.clone(), Box::new(MainModel), centre);
+        let result = integrator.perform_full_integration();
+        assert_eq!(report.samples, 4);
+        assert_eq!(report.probability(result.outcome()), 1.);
+        let summary = report.breaking_scale.unwrap();
+        assert_eq!(summary.count, 4);
+        assert!(summary.quantiles.iter().all(|(_, q)| Some(*q) == result.event_scale()));
+        assert_eq!(report.patterns.len(), 1);
+
+        robustness.distribution = Distribution::Gaussian([0., 0.05, 0.05, 0.05, 0.05, 0.05, 0.05]);
+        let report = analyse_robustness(&params, &MainModel, &robustness);
+        let total: f64 = OUTCOMES.iter().map(|&o| report.probability(o)).sum();
+        assert!((total - 1.).abs() < 1E-12);
+        let breakings: usize = report.patterns.iter().map(|(_, count)| count).sum();
+        assert_eq!(breakings, report.breaking_scale.map_or(0, |s| s.count));
+    }
+}
@@ -41,15 +41,15 @@ impl<const N: usize, const NX: usize, const NY: usize> AllowedConsumer<N, NX, NY
     }
 }
 impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for AllowedConsumer<N, NX, NY> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
-        if let IntegrationResult::Unbroken = result {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        if let IntegrationResult::Unbroken(_) = result {
             return;
         }
 
         let couplings_ref = &couplings.couplings;
 
         match result {
-            IntegrationResult::Broken(_, result) => {
+            IntegrationResult::Broken { result, .. } => {
                 match result {
                     FinalStabilityResult::UnstableAllowed(_) => {
                         for i in 0..N {
@@ -1,5 +1,6 @@
 use crate::model::Couplings;
 use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
 use crate::scanner::scanner::CouplingRanges;
 use crate::simulation::IntegrationResult;
 use crate::util::image::{average_layer, Image, Layer};
@@ -8,15 +9,17 @@ use crate::util::stability::FinalStabilityResult;
 #[derive(Clone)]
 pub struct BreakingScaleConsumer<const N: usize, const NX: usize, const NY: usize> {
     breaking_scale: Box<Layer<(f64, u64), NX, NY>>,
-    index_x: usize,
-    index_y: usize,
+    plane: Plane<N>,
 }
 impl<const N: usize, const NX: usize, const NY: usize> BreakingScaleConsumer<N, NX, NY> {
     pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y))
+    }
+
+    pub fn in_plane(plane: Plane<N>) -> Self {
         Self {
-            breaking_scale: Box::new(average_layer(ranges[index_x], ranges[index_y])),
-            index_x,
-            index_y,
+            breaking_scale: Box::new(average_layer(plane.range_x, plane.range_y)),
+            plane,
         }
     }
 
@@ -33,18 +36,18 @@ impl<const N: usize, const NX: usize, const NY: usize> BreakingScaleConsumer<N,
     }
 }
 impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for BreakingScaleConsumer<N, NX, NY> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
-        if let IntegrationResult::Unbroken = result {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        if let IntegrationResult::Unbroken(_) = result {
             return;
         }
 
-        let couplings_ref = &couplings.couplings;
+        let (x, y) = self.plane.coordinates(&couplings);
 
         match result {
-            IntegrationResult::Broken(log_scale, result) => {
+            IntegrationResult::Broken { log_scale, result, .. } => {
                 match result {
                     FinalStabilityResult::UnstableAllowed(_) => {
-                        self.breaking_scale.write(couplings_ref[self.index_x], couplings_ref[self.index_y], (log_scale, 1));
+                        self.breaking_scale.write(x, y, (log_scale, 1));
                     }
                     _ => {}
                 }
@@ -0,0 +1,81 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::plane_gradient_consumer::allowed_fine_tuning;
+use crate::scanner::consumer::ScanConsumer;
+use crate::simulation::IntegrationResult;
+
+// Passes only allowed breakings with a fine-tuning of at most threshold on to the inner consumer
+#[derive(Clone)]
+pub struct FineTuningFilter<T> {
+    pub consumer: T,
+    pub threshold: f64,
+    // Allowed breakings above the threshold
+    pub rejected: u64,
+}
+impl<T> FineTuningFilter<T> {
+    pub fn new(consumer: T, threshold: f64) -> Self {
+        Self {
+            consumer,
+            threshold,
+            rejected: 0,
+        }
+    }
+}
+impl<const N: usize, T: ScanConsumer<N>> ScanConsumer<N> for FineTuningFilter<T> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        match allowed_fine_tuning(&couplings, &result) {
+            Some(fine_tuning) if fine_tuning <= self.threshold => self.consumer.consume(couplings, result),
+            Some(_) => self.rejected += 1,
+            None => {}
+        }
+    }
+    fn merge(&mut self, other: Self) {
+        self.consumer.merge(other.consumer);
+        self.rejected += other.rejected;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensitivity::Sensitivity;
+    use crate::simulation::EndPoint;
+    use crate::util::stability::{FinalStabilityResult, StabilityResult};
+
+    #[derive(Clone)]
+    struct CountConsumer(u64);
+    impl ScanConsumer<2> for CountConsumer {
+        fn consume(&mut self, _couplings: Couplings<2>, _result: IntegrationResult<2>) {
+            self.0 += 1;
+        }
+        fn merge(&mut self, other: Self) {
+            self.0 += other.0;
+        }
+    }
+
+    fn broken(breaking_scale: [f64; 2]) -> IntegrationResult<2> {
+        let end_point = EndPoint {
+            log_scale: 30.,
+            couplings: Couplings { couplings: [0.; 2] },
+            steps: 1,
+            sensitivity: Some(Sensitivity {
+                couplings: [[1., 0.], [0., 1.]],
+                breaking_scale: Some(breaking_scale),
+            }),
+        };
+        let result = FinalStabilityResult::UnstableAllowed(StabilityResult::Violated1([1., 0., 0.]));
+        IntegrationResult::Broken { log_scale: 30., result, violations: Vec::new(), gildener_weinberg: None, end_point }
+    }
+
+    #[test]
+    fn test_fine_tuning_filter() {
+        let couplings = Couplings { couplings: [0.5, -0.2] };
+        let mut filter = FineTuningFilter::new(CountConsumer(0), 10.);
+        // max(|0.5 * 4|, |-0.2 * 60|) = 12
+        filter.consume(couplings.clone(), broken([4., 60.]));
+        // max(|0.5 * 4|, |-0.2 * -20|) = 4
+        filter.consume(couplings.clone(), broken([4., -20.]));
+        filter.consume(couplings, IntegrationResult::InitiallyUnstable);
+        assert_eq!(filter.rejected, 1);
+        assert_eq!(filter.consumer.0, 1);
+    }
+}
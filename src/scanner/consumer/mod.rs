@@ -8,8 +8,14 @@ pub mod threading_consumer;
 pub mod special_allowed_consumer;
 pub mod multi_special_allowed_consumer;
 pub mod breaking_scale_consumer;
+pub mod violated_condition_consumer;
+pub mod scalon_mass_consumer;
+pub mod record_consumer;
+pub mod plane_gradient_consumer;
+pub mod fine_tuning_consumer;
+pub mod parametrised_consumer;
 
 pub trait ScanConsumer<const N: usize>: Clone {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult);
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>);
     fn merge(&mut self, other: Self);
 }
@@ -1,76 +1,78 @@
-use crate::model::Couplings;
+use crate::model::{BreakingPattern, Couplings, Model};
 use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
 use crate::scanner::scanner::CouplingRanges;
 use crate::simulation::IntegrationResult;
-use crate::util::image::{boolean_layer, color_layer, Image, Layer};
-use crate::util::stability::{FinalStabilityResult, StabilityResult};
-
-const VEV_EPSILON: f64 = 1E-12;
+use crate::util::image::{boolean_layer, count_layer, Image, Layer};
+use crate::util::stability::FinalStabilityResult;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct MultiSpecialAllowedConsumer<const N: usize, const NX: usize, const NY: usize> {
-    broken_allowed: Box<Layer<u32, NX, NY>>,
+    // One layer per breaking pattern of the model
+    broken_allowed: Vec<Layer<i64, NX, NY>>,
+    // Allowed breakings whose vacuum the model could not classify
+    broken_unclassified: Box<Layer<bool, NX, NY>>,
     broken_disallowed: Box<Layer<bool, NX, NY>>,
-    index_x: usize,
-    index_y: usize,
+    plane: Plane<N>,
+    model: Arc<dyn Model<N> + Send + Sync>,
 }
 impl<const N: usize, const NX: usize, const NY: usize> MultiSpecialAllowedConsumer<N, NX, NY> {
-    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize) -> Self {
-        let broken_allowed = Box::new(color_layer(ranges[index_x], ranges[index_y]));
-        let broken_disallowed = Box::new(boolean_layer(ranges[index_x], ranges[index_y]));
+    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize, model: Arc<dyn Model<N> + Send + Sync>) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y), model)
+    }
+
+    pub fn in_plane(plane: Plane<N>, model: Arc<dyn Model<N> + Send + Sync>) -> Self {
+        let broken_allowed = vec![count_layer(plane.range_x, plane.range_y); model.breaking_patterns().len()];
+        let broken_unclassified = Box::new(boolean_layer(plane.range_x, plane.range_y));
+        let broken_disallowed = Box::new(boolean_layer(plane.range_x, plane.range_y));
         
         Self {
             broken_allowed,
+            broken_unclassified,
             broken_disallowed,
-            index_x,
-            index_y,
+            plane,
+            model,
         }
     }
 
-    pub fn render(&self) -> Image<NX, NY> {
+    pub fn patterns(&self) -> &'static [BreakingPattern] {
+        self.model.breaking_patterns()
+    }
+
+    // colors[i] is used for the i-th of the patterns, each pixel shows the most frequent one.
+    // Unclassified allowed breakings are grey.
+    pub fn render(&self, colors: &[u32]) -> Image<NX, NY> {
         let mut image = Image::new();
-        image.draw_color_layer(&self.broken_allowed);
+        image.draw_boolean_layer(&self.broken_unclassified, 0x808080);
+        image.draw_dominant_layers(&self.broken_allowed, colors);
         image.draw_boolean_layer(&self.broken_disallowed, 0xFF0000);
         image
     }
 }
 impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for MultiSpecialAllowedConsumer<N, NX, NY> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
-        if let IntegrationResult::Unbroken = result {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        if let IntegrationResult::Unbroken(_) = result {
             return;
         }
 
-        let couplings_ref = &couplings.couplings;
+        let (x, y) = self.plane.coordinates(&couplings);
 
         match result {
-            IntegrationResult::Broken(_, result) => {
+            IntegrationResult::Broken { result, violations, .. } => {
                 match result {
-                    FinalStabilityResult::UnstableAllowed(stability_result) => {
-                        match stability_result {
-                            StabilityResult::Violated1(vev1) | StabilityResult::Violated2(vev1, _) => {
-                                // We ignore the second vev in this case because it always consists of a +-
-                                if vev1[0].abs() < VEV_EPSILON {
-                                    self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], 0xFFFF00);
-                                } else if vev1[1].abs() < VEV_EPSILON {
-                                    self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], 0x0000FF);
-                                } else if vev1[2].abs() < VEV_EPSILON {
-                                    self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], 0x00FFFF);
-                                } else if (vev1[1] - vev1[2]).abs() < VEV_EPSILON {
-                                    self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], 0x000000);
-                                } else {
-                                    self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], 0x00FF00);
-                                }
-                            }
-                            StabilityResult::Stable => {
-                                panic!("Found stable stability result in broken integration result")
-                            }
-                            StabilityResult::ViolatedReqInit => {
-                                panic!("Found stability violation with large polynomial root gap along RG flow")
-                            }
-                        };
+                    FinalStabilityResult::UnstableAllowed(_) => {
+                        // The first violated condition is the one that determined the result
+                        let pattern = violations.first()
+                            .and_then(|violation| self.model.classify_vacuum(violation));
+                        let index = pattern.and_then(|pattern| self.patterns().iter().position(|&p| p == pattern));
+                        match index {
+                            Some(index) => self.broken_allowed[index].write(x, y, 1),
+                            None => self.broken_unclassified.write(x, y, true),
+                        }
                     }
                     FinalStabilityResult::UnstableDisallowed(_) => {
-                        self.broken_disallowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], true);
+                        self.broken_disallowed.write(x, y, true);
                     }
                     _ => {}
                 }
@@ -79,7 +81,10 @@ impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for Multi
         }
     }
     fn merge(&mut self, other: Self) {
-        self.broken_allowed.merge(&other.broken_allowed);
+        for (layer, other_layer) in self.broken_allowed.iter_mut().zip(&other.broken_allowed) {
+            layer.merge(other_layer);
+        }
+        self.broken_unclassified.merge(&other.broken_unclassified);
         self.broken_disallowed.merge(&other.broken_disallowed);
     }
 }
@@ -0,0 +1,27 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::parametrisation::Parametrisation;
+use crate::simulation::IntegrationResult;
+
+// Passes the coordinates of the parametrisation on to the inner consumer in place of the initial
+// couplings, so that it bins in these coordinates. The inner consumer is set up with coordinate
+// ranges.
+#[derive(Clone)]
+pub struct ParametrisedConsumer<T, const N: usize> {
+    pub consumer: T,
+    pub parametrisation: Parametrisation<N>,
+}
+impl<T, const N: usize> ParametrisedConsumer<T, N> {
+    pub fn new(consumer: T, parametrisation: Parametrisation<N>) -> Self {
+        Self { consumer, parametrisation }
+    }
+}
+impl<const N: usize, T: ScanConsumer<N>> ScanConsumer<N> for ParametrisedConsumer<T, N> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        let coordinates = self.parametrisation.coordinates(&couplings);
+        self.consumer.consume(Couplings { couplings: coordinates }, result);
+    }
+    fn merge(&mut self, other: Self) {
+        self.consumer.merge(other.consumer);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
+use crate::scanner::scanner::CouplingRanges;
+use crate::simulation::IntegrationResult;
+use crate::util::image::{average_layer, Image, Layer};
+use crate::util::stability::FinalStabilityResult;
+
+// Value drawn for a result, if any
+pub type Extractor<const N: usize> = fn(&Couplings<N>, &IntegrationResult<N>) -> Option<f64>;
+
+// Magnitude of the gradient of the breaking scale with respect to all initial couplings for allowed
+// breakings, which requires sensitivity to be enabled in the integration parameters
+pub fn allowed_sensitivity<const N: usize>(_couplings: &Couplings<N>, result: &IntegrationResult<N>) -> Option<f64> {
+    let IntegrationResult::Broken { result: FinalStabilityResult::UnstableAllowed(_), end_point, .. } = result else {
+        return None;
+    };
+    end_point.sensitivity.as_ref()?.breaking_scale_gradient_magnitude()
+}
+
+// Fine-tuning of the breaking scale of allowed breakings, which requires sensitivity to be enabled
+// in the integration parameters
+pub fn allowed_fine_tuning<const N: usize>(couplings: &Couplings<N>, result: &IntegrationResult<N>) -> Option<f64> {
+    let IntegrationResult::Broken { result: FinalStabilityResult::UnstableAllowed(_), end_point, .. } = result else {
+        return None;
+    };
+    end_point.sensitivity.as_ref()?.fine_tuning(couplings)
+}
+
+// log10 of the extracted value over a plane. Values that are not positive have no logarithm and are
+// skipped.
+#[derive(Clone)]
+pub struct PlaneGradientConsumer<const N: usize, const NX: usize, const NY: usize> {
+    values: Box<Layer<(f64, u64), NX, NY>>,
+    plane: Plane<N>,
+    extractor: Extractor<N>,
+}
+impl<const N: usize, const NX: usize, const NY: usize> PlaneGradientConsumer<N, NX, NY> {
+    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize, extractor: Extractor<N>) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y), extractor)
+    }
+
+    pub fn in_plane(plane: Plane<N>, extractor: Extractor<N>) -> Self {
+        Self {
+            values: Box::new(average_layer(plane.range_x, plane.range_y)),
+            plane,
+            extractor,
+        }
+    }
+
+    pub fn render(&self) -> (Image<NX, NY>, f64, f64) {
+        let mut image = Image::new();
+        let (min, max) = image.draw_gradient_layer(&self.values, 0x00FF00, 0x0000FF);
+        (image, min, max)
+    }
+
+    pub fn render_with_range(&self, min: f64, max: f64) -> Image<NX, NY> {
+        let mut image = Image::new();
+        image.draw_gradient_layer_with_range(&self.values, 0x00FF00, 0x0000FF, min, max);
+        image
+    }
+}
+impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for PlaneGradientConsumer<N, NX, NY> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        let Some(value) = (self.extractor)(&couplings, &result).filter(|&value| value > 0.) else {
+            return;
+        };
+        let (x, y) = self.plane.coordinates(&couplings);
+        self.values.write(x, y, (value.log10(), 1));
+    }
+    fn merge(&mut self, other: Self) {
+        self.values.merge(&other.values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensitivity::Sensitivity;
+    use crate::simulation::EndPoint;
+    use crate::util::stability::StabilityResult;
+
+    fn broken(breaking_scale: [f64; 2]) -> IntegrationResult<2> {
+        let end_point = EndPoint {
+            log_scale: 30.,
+            couplings: Couplings { couplings: [0.; 2] },
+            steps: 1,
+            sensitivity: Some(Sensitivity {
+                couplings: [[1., 0.], [0., 1.]],
+                breaking_scale: Some(breaking_scale),
+            }),
+        };
+        let result = FinalStabilityResult::UnstableAllowed(StabilityResult::Violated1([1., 0., 0.]));
+        IntegrationResult::Broken { log_scale: 30., result, violations: Vec::new(), gildener_weinberg: None, end_point }
+    }
+
+    #[test]
+    fn test_non_positive_values_are_skipped() {
+        let mut consumer: PlaneGradientConsumer<2, 4, 4> =
+            PlaneGradientConsumer::new([(-1., 1.), (-1., 1.)], 0, 1, allowed_fine_tuning);
+        // Vanishing fine-tuning at vanishing couplings
+        consumer.consume(Couplings { couplings: [0., 0.] }, broken([4., 60.]));
+        // max(|0.5 * 200|, |0.5 * 0|) = 100
+        consumer.consume(Couplings { couplings: [0.5, 0.5] }, broken([200., 0.]));
+        let (_, min, max) = consumer.render();
+        assert_eq!((min, max), (2., 2.));
+    }
+}
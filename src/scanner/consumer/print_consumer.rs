@@ -5,16 +5,37 @@ use crate::simulation::IntegrationResult;
 #[derive(Clone)]
 pub struct PrintConsumer<const N: usize>;
 impl<const N: usize> ScanConsumer<N> for PrintConsumer<N> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
         print!("Couplings: {:?} => ", couplings.couplings);
         match result {
-            IntegrationResult::Unbroken => println!("Unbroken"),
+            IntegrationResult::Unbroken(end_point) => {
+                println!("Unbroken, final couplings: {:?}", end_point.couplings.couplings)
+            }
             IntegrationResult::InitiallyUnstable => println!("Initially Unstable"),
-            IntegrationResult::PerturbativityViolated(scale) => {
-                println!("Perturbativity Violated at scale {}", scale)
+            IntegrationResult::PerturbativityViolated(scale, violation, end_point) => {
+                println!("Perturbativity Violated at scale {}: {:?}", scale, violation);
+                println!("    after {} steps, couplings: {:?}", end_point.steps, end_point.couplings.couplings);
+            }
+            IntegrationResult::Broken {
+                log_scale: scale,
+                result: stability_result,
+                violations,
+                gildener_weinberg,
+                end_point,
+            } => {
+                println!("Broken at scale {}: {:?}, violated: {:?}", scale, stability_result, violations);
+                println!("    after {} steps, couplings: {:?}", end_point.steps, end_point.couplings.couplings);
+                if let Some(gildener_weinberg) = gildener_weinberg {
+                    println!(
+                        "    B = {}, scalon mass = {} v, minimum: {}",
+                        gildener_weinberg.b,
+                        gildener_weinberg.scalon_mass,
+                        gildener_weinberg.is_minimum()
+                    );
+                }
             }
-            IntegrationResult::Broken(scale, stability_result) => {
-                println!("Broken at scale {}: {:?}", scale, stability_result)
+            IntegrationResult::Diverged(scale, divergence) => {
+                println!("Diverged at scale {}: {:?}", scale, divergence)
             }
             IntegrationResult::Invalid => panic!()
         }
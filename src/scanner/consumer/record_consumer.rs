@@ -0,0 +1,60 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::ScanConsumer;
+use crate::simulation::IntegrationResult;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+// Records every sample together with the couplings where the integration stopped
+#[derive(Clone)]
+pub struct EventRecordConsumer<const N: usize> {
+    rows: Vec<String>,
+}
+impl<const N: usize> EventRecordConsumer<N> {
+    pub fn new() -> Self {
+        Self { rows: Vec::new() }
+    }
+
+    fn header() -> String {
+        let initial = (0..N).map(|i| format!("initial_{}", i));
+        let end = (0..N).map(|i| format!("end_{}", i));
+        initial
+            .chain(["outcome".to_string(), "scale".to_string(), "end_scale".to_string(), "steps".to_string()])
+            .chain(end)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn save_to_csv(&self, filename: &str) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        writeln!(writer, "{}", Self::header())?;
+        for row in &self.rows {
+            writeln!(writer, "{}", row)?;
+        }
+        writer.flush()
+    }
+}
+impl<const N: usize> Default for EventRecordConsumer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> ScanConsumer<N> for EventRecordConsumer<N> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        let end_point = result.end_point();
+        let format = |x: Option<f64>| x.map_or(String::new(), |x| x.to_string());
+        let mut fields: Vec<String> = couplings.couplings.iter().map(|c| c.to_string()).collect();
+        fields.push(result.outcome().name().to_string());
+        fields.push(format(result.event_scale()));
+        fields.push(format(end_point.map(|e| e.log_scale)));
+        fields.push(end_point.map_or(String::new(), |e| e.steps.to_string()));
+        match end_point {
+            Some(end_point) => fields.extend(end_point.couplings.couplings.iter().map(|c| c.to_string())),
+            None => fields.extend((0..N).map(|_| String::new())),
+        }
+        self.rows.push(fields.join(","));
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.rows.extend(other.rows);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
+use crate::scanner::scanner::CouplingRanges;
+use crate::simulation::IntegrationResult;
+use crate::util::image::{average_layer, boolean_layer, Image, Layer};
+
+// Scalon mass in units of the vev for allowed breakings, which requires gildener_weinberg to be
+// enabled in the integration parameters
+#[derive(Clone)]
+pub struct ScalonMassConsumer<const N: usize, const NX: usize, const NY: usize> {
+    scalon_mass: Box<Layer<(f64, u64), NX, NY>>,
+    // Points where no radiative minimum forms along the flat direction
+    no_minimum: Box<Layer<bool, NX, NY>>,
+    plane: Plane<N>,
+}
+impl<const N: usize, const NX: usize, const NY: usize> ScalonMassConsumer<N, NX, NY> {
+    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y))
+    }
+
+    pub fn in_plane(plane: Plane<N>) -> Self {
+        Self {
+            scalon_mass: Box::new(average_layer(plane.range_x, plane.range_y)),
+            no_minimum: Box::new(boolean_layer(plane.range_x, plane.range_y)),
+            plane,
+        }
+    }
+
+    pub fn render(&self) -> (Image<NX, NY>, f64, f64) {
+        let mut image = Image::new();
+        let (min, max) = image.draw_gradient_layer(&self.scalon_mass, 0x00FF00, 0x0000FF);
+        image.draw_boolean_layer(&self.no_minimum, 0xFF0000);
+        (image, min, max)
+    }
+}
+impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for ScalonMassConsumer<N, NX, NY> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        let IntegrationResult::Broken { gildener_weinberg: Some(gildener_weinberg), .. } = result else {
+            return;
+        };
+
+        let (x, y) = self.plane.coordinates(&couplings);
+        if gildener_weinberg.is_minimum() {
+            self.scalon_mass.write(x, y, (gildener_weinberg.scalon_mass, 1));
+        } else {
+            self.no_minimum.write(x, y, true);
+        }
+    }
+    fn merge(&mut self, other: Self) {
+        self.scalon_mass.merge(&other.scalon_mass);
+        self.no_minimum.merge(&other.no_minimum);
+    }
+}
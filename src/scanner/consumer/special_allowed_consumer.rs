@@ -1,32 +1,40 @@
-use crate::model::Couplings;
+use crate::model::{Couplings, Model};
 use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
 use crate::scanner::scanner::CouplingRanges;
 use crate::simulation::IntegrationResult;
 use crate::util::image::{boolean_layer, Image, Layer};
-use crate::util::stability::{FinalStabilityResult, StabilityResult};
-
-const VEV_EPSILON: f64 = 1E-12;
+use crate::util::stability::FinalStabilityResult;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct SpecialAllowedConsumer<const N: usize, const NX: usize, const NY: usize> {
     broken_allowed: Box<Layer<bool, NX, NY>>,
     broken_super: Box<Layer<bool, NX, NY>>,
+    // Allowed breakings whose vacuum the model could not classify
+    broken_unclassified: Box<Layer<bool, NX, NY>>,
     broken_disallowed: Box<Layer<bool, NX, NY>>,
-    index_x: usize,
-    index_y: usize,
+    plane: Plane<N>,
+    model: Arc<dyn Model<N> + Send + Sync>,
 }
 impl<const N: usize, const NX: usize, const NY: usize> SpecialAllowedConsumer<N, NX, NY> {
-    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize) -> Self {
-        let broken_allowed = Box::new(boolean_layer(ranges[index_x], ranges[index_y]));
-        let broken_super = Box::new(boolean_layer(ranges[index_x], ranges[index_y]));
-        let broken_disallowed = Box::new(boolean_layer(ranges[index_x], ranges[index_y]));
+    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize, model: Arc<dyn Model<N> + Send + Sync>) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y), model)
+    }
+
+    pub fn in_plane(plane: Plane<N>, model: Arc<dyn Model<N> + Send + Sync>) -> Self {
+        let broken_allowed = Box::new(boolean_layer(plane.range_x, plane.range_y));
+        let broken_super = Box::new(boolean_layer(plane.range_x, plane.range_y));
+        let broken_unclassified = Box::new(boolean_layer(plane.range_x, plane.range_y));
+        let broken_disallowed = Box::new(boolean_layer(plane.range_x, plane.range_y));
 
         Self {
             broken_allowed,
             broken_super,
+            broken_unclassified,
             broken_disallowed,
-            index_x,
-            index_y,
+            plane,
+            model,
         }
     }
 
@@ -34,46 +42,35 @@ impl<const N: usize, const NX: usize, const NY: usize> SpecialAllowedConsumer<N,
         let mut image = Image::new();
         image.draw_boolean_layer(&self.broken_allowed, 0x00FF00);
         image.draw_boolean_layer(&self.broken_super, 0x0000FF);
+        image.draw_boolean_layer(&self.broken_unclassified, 0x808080);
         image.draw_boolean_layer(&self.broken_disallowed, 0xFF0000);
         image
     }
 }
 impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for SpecialAllowedConsumer<N, NX, NY> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
-        if let IntegrationResult::Unbroken = result {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        if let IntegrationResult::Unbroken(_) = result {
             return;
         }
 
-        let couplings_ref = &couplings.couplings;
+        let (x, y) = self.plane.coordinates(&couplings);
 
         match result {
-            IntegrationResult::Broken(_, result) => {
+            IntegrationResult::Broken { result, violations, .. } => {
                 match result {
-                    FinalStabilityResult::UnstableAllowed(stability_result) => {
-                        let supergroup = match stability_result {
-                            StabilityResult::Violated1(vev1) | StabilityResult::Violated2(vev1, _) => {
-                                // We ignore the second vev in this case because it always consists of a +-
-                                vev1[0].abs() < VEV_EPSILON || 
-                                    vev1[1].abs() < VEV_EPSILON || 
-                                    vev1[2].abs() < VEV_EPSILON ||
-                                    (vev1[1] - vev1[2]).abs() < VEV_EPSILON
-                            }
-                            StabilityResult::Stable => {
-                                panic!("Found stable stability result in broken integration result")
-                            }
-                            StabilityResult::ViolatedReqInit => {
-                                panic!("Found stability violation with large polynomial root gap along RG flow")
-                            }
-                        };
-                        
-                        if supergroup {
-                            self.broken_super.write(couplings_ref[self.index_x], couplings_ref[self.index_y], true);
-                        } else {
-                            self.broken_allowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], true);
+                    FinalStabilityResult::UnstableAllowed(_) => {
+                        // The first violated condition is the one that determined the result
+                        let pattern = violations.first()
+                            .and_then(|violation| self.model.classify_vacuum(violation));
+
+                        match pattern {
+                            Some(pattern) if pattern.supergroup => self.broken_super.write(x, y, true),
+                            Some(_) => self.broken_allowed.write(x, y, true),
+                            None => self.broken_unclassified.write(x, y, true),
                         }
                     }
                     FinalStabilityResult::UnstableDisallowed(_) => {
-                        self.broken_disallowed.write(couplings_ref[self.index_x], couplings_ref[self.index_y], true);
+                        self.broken_disallowed.write(x, y, true);
                     }
                     _ => {}
                 }
@@ -84,6 +81,7 @@ impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for Speci
     fn merge(&mut self, other: Self) {
         self.broken_allowed.merge(&other.broken_allowed);
         self.broken_super.merge(&other.broken_super);
+        self.broken_unclassified.merge(&other.broken_unclassified);
         self.broken_disallowed.merge(&other.broken_disallowed);
     }
 }
@@ -45,15 +45,15 @@ impl<const N: usize, const NX: usize, const NY: usize> StabilityConsumer<N, NX,
     }
 }
 impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for StabilityConsumer<N, NX, NY> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
-        if let IntegrationResult::Unbroken = result {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        if let IntegrationResult::Unbroken(_) = result {
             return;
         }
 
         let couplings_ref = &couplings.couplings;
 
         match result {
-            IntegrationResult::PerturbativityViolated(_) => {
+            IntegrationResult::PerturbativityViolated(_, _, _) => {
                 for i in 0..N {
                     for j in 0..N {
                         let index = i * N + j;
@@ -61,7 +61,7 @@ impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for Stabi
                     }
                 }
             }
-            IntegrationResult::Broken(_, _) => {
+            IntegrationResult::Broken { .. } => {
                 for i in 0..N {
                     for j in 0..N {
                         let index = i * N + j;
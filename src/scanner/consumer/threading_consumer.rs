@@ -5,10 +5,10 @@ use std::sync::mpsc::Sender;
 
 #[derive(Clone)]
 pub struct SendConsumer<const N: usize> {
-    pub sender: Sender<(Couplings<N>, IntegrationResult)>,
+    pub sender: Sender<(Couplings<N>, IntegrationResult<N>)>,
 }
 impl<const N: usize> ScanConsumer<N> for SendConsumer<N> {
-    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult) {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
         self.sender.send((couplings, result)).unwrap();
     }
 
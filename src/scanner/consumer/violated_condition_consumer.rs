@@ -0,0 +1,59 @@
+use crate::model::Couplings;
+use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::plane::Plane;
+use crate::scanner::scanner::CouplingRanges;
+use crate::simulation::IntegrationResult;
+use crate::util::image::{count_layer, Image, Layer};
+
+// Counts, for every stability condition of the model, how often it is violated at the breaking
+// scale, so that regions can be colored by the condition responsible for the breaking
+#[derive(Clone)]
+pub struct ViolatedConditionConsumer<const N: usize, const NX: usize, const NY: usize> {
+    violated: Vec<Layer<i64, NX, NY>>,
+    plane: Plane<N>,
+}
+impl<const N: usize, const NX: usize, const NY: usize> ViolatedConditionConsumer<N, NX, NY> {
+    pub fn new(ranges: CouplingRanges<N>, index_x: usize, index_y: usize, num_conditions: usize) -> Self {
+        Self::in_plane(Plane::coupling_plane(ranges, index_x, index_y), num_conditions)
+    }
+
+    pub fn in_plane(plane: Plane<N>, num_conditions: usize) -> Self {
+        let violated = vec![count_layer(plane.range_x, plane.range_y); num_conditions];
+
+        Self {
+            violated,
+            plane,
+        }
+    }
+
+    // One image per condition, showing where it is violated at the breaking scale
+    pub fn render_condition(&self, condition: usize, color: u32) -> Image<NX, NY> {
+        let mut image = Image::new();
+        image.draw_dominant_layers(&self.violated[condition..=condition], &[color]);
+        image
+    }
+
+    // colors[i] is used for the i-th stability condition of the model
+    pub fn render(&self, colors: &[u32]) -> Image<NX, NY> {
+        let mut image = Image::new();
+        image.draw_dominant_layers(&self.violated, colors);
+        image
+    }
+}
+impl<const N: usize, const NX: usize, const NY: usize> ScanConsumer<N> for ViolatedConditionConsumer<N, NX, NY> {
+    fn consume(&mut self, couplings: Couplings<N>, result: IntegrationResult<N>) {
+        let IntegrationResult::Broken { violations, .. } = result else {
+            return;
+        };
+
+        let (x, y) = self.plane.coordinates(&couplings);
+        for violation in violations {
+            self.violated[violation.index].write(x, y, 1);
+        }
+    }
+    fn merge(&mut self, other: Self) {
+        for (layer, other_layer) in self.violated.iter_mut().zip(&other.violated) {
+            layer.merge(other_layer);
+        }
+    }
+}
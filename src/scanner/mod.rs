@@ -1,3 +1,6 @@
 pub mod scanner;
 pub mod consumer;
 pub mod multi_threaded_scanner;
+pub mod plane;
+pub mod parametrisation;
+pub mod sampler;
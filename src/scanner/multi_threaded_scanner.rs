@@ -1,17 +1,23 @@
 use crate::model::Model;
 use crate::scanner::consumer::ScanConsumer;
+use crate::shared_running::SharedRunning;
 use crate::simulation::IntegrationParameters;
+use std::sync::Arc;
 use std::thread;
 use indicatif::{ProgressBar, ProgressStyle};
-use crate::scanner::scanner::Scanner;
+use crate::scanner::plane::Plane;
+use crate::scanner::sampler::Sampling;
+use crate::scanner::scanner::{SampleSpace, ScanStatistics, Scanner};
 
 pub type CouplingRanges<const N: usize> = [(f64, f64); N];
 
 pub struct MultiThreadedScanner<M: Model<N> + Clone, T: ScanConsumer<N>, const N: usize> {
-    coupling_ranges: CouplingRanges<N>,
+    space: SampleSpace<N>,
+    pub sampling: Sampling,
     params: IntegrationParameters,
     model: M,
     pub consumer: T,
+    pub statistics: ScanStatistics,
 }
 impl<M: Model<N> + Clone + Send + 'static, T: ScanConsumer<N> + Send + 'static, const N: usize> MultiThreadedScanner<M, T, N> {
     pub fn new(
@@ -20,11 +26,22 @@ impl<M: Model<N> + Clone + Send + 'static, T: ScanConsumer<N> + Send + 'static,
         model: M,
         consumer: T,
     ) -> Self {
+        Self::with_space(SampleSpace::Ranges(coupling_ranges), params, model, consumer)
+    }
+
+    // Samples uniformly in the plane coordinates
+    pub fn in_plane(plane: Plane<N>, params: IntegrationParameters, model: M, consumer: T) -> Self {
+        Self::with_space(SampleSpace::Plane(plane), params, model, consumer)
+    }
+
+    pub fn with_space(space: SampleSpace<N>, params: IntegrationParameters, model: M, consumer: T) -> Self {
         Self {
-            coupling_ranges,
+            space,
+            sampling: Sampling::Uniform,
             params,
             model,
             consumer,
+            statistics: ScanStatistics::default(),
         }
     }
 
@@ -42,25 +59,38 @@ impl<M: Model<N> + Clone + Send + 'static, T: ScanConsumer<N> + Send + 'static,
             num_threads, num_samples
         );
 
+        // Samples share the running of the independent couplings if they start with the values of
+        // this one
+        let shared_running = SharedRunning::new(&self.model, &self.params, &self.space.sample()).map(Arc::new);
+        if let Some(running) = &shared_running {
+            println!("Sharing the running of couplings {:?}", running.indices);
+        }
+
         let mut join_handles: Vec<_> = (0..num_threads)
             .map(|_| {
                 let tx = tx.clone();
                 let mut send_consumer = self.consumer.clone();
 
-                let coupling_ranges = self.coupling_ranges;
+                let space = self.space.clone();
+                let sampling = self.sampling;
                 let params = self.params.clone();
                 let model = self.model.clone();
+                let shared_running = shared_running.clone();
 
                 thread::spawn(move || {
                     let mut scanner = Scanner::new(
-                        coupling_ranges,
+                        space,
+                        sampling,
                         params,
                         Box::new(model),
                     );
+                    if let Some(running) = shared_running {
+                        scanner.share_running(running);
+                    }
 
-                    scanner.scan(num_samples, &mut send_consumer, tx);
+                    let statistics = scanner.scan(num_samples, &mut send_consumer, tx);
 
-                    send_consumer
+                    (send_consumer, statistics)
                 })
             })
             .collect();
@@ -79,8 +109,9 @@ impl<M: Model<N> + Clone + Send + 'static, T: ScanConsumer<N> + Send + 'static,
             for i in (0..join_handles.len()).rev() {
                 if join_handles[i].is_finished() {
                     // Join and remove the finished handle
-                    let consumer = join_handles.swap_remove(i).join().unwrap();
+                    let (consumer, statistics) = join_handles.swap_remove(i).join().unwrap();
                     self.consumer.merge(consumer);
+                    self.statistics.merge(&statistics);
                 }
             }
 
@@ -88,5 +119,13 @@ impl<M: Model<N> + Clone + Send + 'static, T: ScanConsumer<N> + Send + 'static,
                 progress_bar.inc(i);
             }
         }
+
+        println!("Invalids: {}, Diverged: {}", self.statistics.invalid, self.statistics.diverged);
+        if self.statistics.no_stable_couplings {
+            println!("Stopped early, no stable couplings were found");
+        }
+        if let Some(fraction) = self.statistics.stable_fraction() {
+            println!("Stable fraction: {} of {} proposals", fraction, self.statistics.proposals);
+        }
     }
 }
@@ -0,0 +1,150 @@
+use crate::model::Couplings;
+use std::f64::consts::PI;
+
+// Coordinates in which samples are drawn uniformly and consumers can bin. Every variant maps N
+// coordinates to the N couplings.
+#[derive(Debug, Clone)]
+pub enum Parametrisation<const N: usize> {
+    // The couplings themselves
+    Linear,
+    // The gauge coupling g at the index and lambda_i / g^2 for every other coupling
+    Ratios(usize),
+    // The gauge coupling at the index, then the norm of the other couplings followed by their
+    // hyperspherical angles in the order of the couplings. The angles range over [0, pi] except the
+    // last one, which ranges over [-pi, pi]. Note that uniform angles are not uniform on the sphere.
+    Hyperspherical(usize),
+    // Signed logarithmic magnitudes x = sign(lambda) log10(|lambda| / floor), which are log-uniform
+    // in magnitude between floor and floor 10^|x| with both signs. Couplings without a floor stay
+    // linear. Magnitudes below the floor have x = 0, which maps back to a vanishing coupling.
+    SignedLog([Option<f64>; N]),
+}
+impl<const N: usize> Parametrisation<N> {
+    pub fn couplings(&self, coordinates: &[f64; N]) -> Couplings<N> {
+        let couplings = match self {
+            Parametrisation::Linear => *coordinates,
+            Parametrisation::Ratios(gauge_index) => {
+                let g2 = coordinates[*gauge_index].powi(2);
+                std::array::from_fn(|i| if i == *gauge_index { coordinates[i] } else { g2 * coordinates[i] })
+            }
+            Parametrisation::Hyperspherical(gauge_index) => {
+                let others = other_indices::<N>(*gauge_index);
+                let mut couplings = [0.; N];
+                couplings[*gauge_index] = coordinates[*gauge_index];
+                // r cos phi_1, r sin phi_1 cos phi_2, ..., r sin phi_1 ... sin phi_n-1
+                let mut radius = coordinates[others[0]];
+                for (k, &index) in others.iter().enumerate() {
+                    match others.get(k + 1) {
+                        Some(&angle_index) => {
+                            couplings[index] = radius * coordinates[angle_index].cos();
+                            radius *= coordinates[angle_index].sin();
+                        }
+                        None => couplings[index] = radius,
+                    }
+                }
+                couplings
+            }
+            Parametrisation::SignedLog(floors) => std::array::from_fn(|i| match floors[i] {
+                Some(_) if coordinates[i] == 0. => 0.,
+                Some(floor) => coordinates[i].signum() * floor * 10_f64.powf(coordinates[i].abs()),
+                None => coordinates[i],
+            }),
+        };
+        Couplings { couplings }
+    }
+
+    pub fn coordinates(&self, couplings: &Couplings<N>) -> [f64; N] {
+        let couplings = &couplings.couplings;
+        match self {
+            Parametrisation::Linear => *couplings,
+            Parametrisation::Ratios(gauge_index) => {
+                let g2 = couplings[*gauge_index].powi(2);
+                std::array::from_fn(|i| if i == *gauge_index { couplings[i] } else { couplings[i] / g2 })
+            }
+            Parametrisation::Hyperspherical(gauge_index) => {
+                let others = other_indices::<N>(*gauge_index);
+                let mut coordinates = [0.; N];
+                coordinates[*gauge_index] = couplings[*gauge_index];
+                // Norm of the couplings from others[k] on
+                let mut tail = others.iter().map(|&i| couplings[i].powi(2)).sum::<f64>().sqrt();
+                coordinates[others[0]] = tail;
+                for k in 0..others.len().saturating_sub(1) {
+                    let angle_index = others[k + 1];
+                    let (x, y) = (couplings[others[k]], couplings[others[k + 1]]);
+                    let rest = (tail * tail - x * x).max(0.).sqrt();
+                    coordinates[angle_index] = if k + 2 == others.len() { y.atan2(x) } else { rest.atan2(x) };
+                    tail = rest;
+                }
+                coordinates
+            }
+            Parametrisation::SignedLog(floors) => std::array::from_fn(|i| match floors[i] {
+                Some(floor) => couplings[i].signum() * (couplings[i].abs() / floor).log10().max(0.),
+                None => couplings[i],
+            }),
+        }
+    }
+
+    // Coordinate ranges for the hyperspherical parametrisation with the gauge coupling within
+    // gauge_range and a fixed norm of the other couplings
+    pub fn hyperspherical_ranges(gauge_index: usize, gauge_range: (f64, f64), norm: f64) -> [(f64, f64); N] {
+        let others = other_indices::<N>(gauge_index);
+        std::array::from_fn(|i| {
+            if i == gauge_index {
+                gauge_range
+            } else if i == others[0] {
+                (norm, norm)
+            } else if Some(&i) == others.last() {
+                (-PI, PI)
+            } else {
+                (0., PI)
+            }
+        })
+    }
+}
+
+fn other_indices<const N: usize>(gauge_index: usize) -> Vec<usize> {
+    (0..N).filter(|&i| i != gauge_index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parametrisation_round_trip() {
+        let couplings = Couplings {
+            couplings: [0.6, 0.3, -0.3, 0.1, 0.002, 0.1, -0.05],
+        };
+        let parametrisations = [
+            Parametrisation::Linear,
+            Parametrisation::Ratios(0),
+            Parametrisation::Hyperspherical(0),
+            Parametrisation::SignedLog([None, Some(1E-3), Some(1E-3), Some(1E-3), Some(1E-3), Some(1E-3), Some(1E-3)]),
+        ];
+        for parametrisation in parametrisations {
+            let coordinates = parametrisation.coordinates(&couplings);
+            let round_trip = parametrisation.couplings(&coordinates);
+            for (a, b) in round_trip.couplings.iter().zip(couplings.couplings) {
+                assert!((a - b).abs() < 1E-14, "{:?}: {} != {}", parametrisation, a, b);
+            }
+        }
+
+        // Vanishing couplings and those below the floor map to x = 0 and back to 0
+        let signed_log = Parametrisation::SignedLog([Some(1E-3); 3]);
+        let small = Couplings { couplings: [0., 1E-4, -1E-4] };
+        assert_eq!(signed_log.coordinates(&small), [0.; 3]);
+        assert_eq!(signed_log.couplings(&signed_log.coordinates(&small)).couplings, [0.; 3]);
+
+        let coordinates = Parametrisation::Ratios(0).coordinates(&couplings);
+        assert!((coordinates[1] - 0.3 / 0.36).abs() < 1E-15);
+
+        // Every sample in the hyperspherical ranges has the fixed norm
+        let ranges = Parametrisation::<7>::hyperspherical_ranges(0, (0.5, 0.5), 0.4);
+        let coordinates: [f64; 7] = std::array::from_fn(|i| {
+            let (min, max) = ranges[i];
+            min + rand::random::<f64>() * (max - min)
+        });
+        let sample = Parametrisation::Hyperspherical(0).couplings(&coordinates);
+        let norm = sample.couplings[1..].iter().map(|x| x * x).sum::<f64>().sqrt();
+        assert!((norm - 0.4).abs() < 1E-14 && sample.couplings[0] == 0.5);
+    }
+}
@@ -0,0 +1,109 @@
+use crate::model::Couplings;
+use crate::scanner::scanner::CouplingRanges;
+
+// Gram determinants below this fraction of the product of the squared lengths of the directions
+// count as parallel directions
+const PARALLEL_TOLERANCE: f64 = 1E-12;
+
+// The affine plane origin + x directions[0] + y directions[1] of coupling space, for x and y within
+// the ranges. Images of consumers are drawn in the plane coordinates (x, y).
+#[derive(Debug, Clone)]
+pub struct Plane<const N: usize> {
+    origin: Couplings<N>,
+    directions: [[f64; N]; 2],
+    pub range_x: (f64, f64),
+    pub range_y: (f64, f64),
+}
+impl<const N: usize> Plane<N> {
+    // None if the directions are parallel, or one of them vanishes, so that they do not span a plane
+    pub fn new(
+        origin: Couplings<N>,
+        directions: [[f64; N]; 2],
+        range_x: (f64, f64),
+        range_y: (f64, f64),
+    ) -> Option<Self> {
+        let [a, b] = &directions;
+        let (aa, ab, bb) = (dot(a, a), dot(a, b), dot(b, b));
+        if aa * bb - ab * ab <= PARALLEL_TOLERANCE * aa * bb {
+            return None;
+        }
+        Some(Self { origin, directions, range_x, range_y })
+    }
+
+    // The plane of two couplings, which are then the plane coordinates, with every other coupling at
+    // the middle of its range
+    pub fn coupling_plane(ranges: CouplingRanges<N>, index_x: usize, index_y: usize) -> Self {
+        let couplings = std::array::from_fn(|i| {
+            if i == index_x || i == index_y { 0. } else { 0.5 * (ranges[i].0 + ranges[i].1) }
+        });
+        let unit = |index: usize| std::array::from_fn(|i| if i == index { 1. } else { 0. });
+        Self {
+            origin: Couplings { couplings },
+            directions: [unit(index_x), unit(index_y)],
+            range_x: ranges[index_x],
+            range_y: ranges[index_y],
+        }
+    }
+
+    pub fn couplings(&self, x: f64, y: f64) -> Couplings<N> {
+        Couplings {
+            couplings: std::array::from_fn(|i| {
+                self.origin.couplings[i] + x * self.directions[0][i] + y * self.directions[1][i]
+            }),
+        }
+    }
+
+    // Plane coordinates of the orthogonal projection of the couplings onto the plane
+    pub fn coordinates(&self, couplings: &Couplings<N>) -> (f64, f64) {
+        let [a, b] = &self.directions;
+        let offset = std::array::from_fn(|i| couplings.couplings[i] - self.origin.couplings[i]);
+        let (aa, ab, bb) = (dot(a, a), dot(a, b), dot(b, b));
+        let (a_offset, b_offset) = (dot(a, &offset), dot(b, &offset));
+        let determinant = aa * bb - ab * ab;
+        ((bb * a_offset - ab * b_offset) / determinant, (aa * b_offset - ab * a_offset) / determinant)
+    }
+
+    pub fn sample(&self) -> Couplings<N> {
+        let (x_min, x_max) = self.range_x;
+        let (y_min, y_max) = self.range_y;
+        self.couplings(
+            rand::random::<f64>() * (x_max - x_min) + x_min,
+            rand::random::<f64>() * (y_max - y_min) + y_min,
+        )
+    }
+}
+
+fn dot<const N: usize>(u: &[f64; N], v: &[f64; N]) -> f64 {
+    u.iter().zip(v).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plane_coordinates() {
+        let origin = Couplings { couplings: [0.425, 0.3, -0.3, 0.1] };
+        let plane = Plane::new(origin, [[0., 1., 1., 0.], [0., 0.5, 0., 2.]], (-0.5, 0.5), (-0.2, 0.2)).unwrap();
+        let (x, y) = plane.coordinates(&plane.couplings(0.3, -0.1));
+        assert!((x - 0.3).abs() < 1E-14 && (y + 0.1).abs() < 1E-14);
+
+        // Coupling planes have the couplings themselves as coordinates
+        let mut ranges = [(0.1, 0.1); 4];
+        ranges[1] = (-0.5, 0.5);
+        ranges[3] = (-1., 1.);
+        let plane = Plane::coupling_plane(ranges, 1, 3);
+        let couplings = plane.sample();
+        assert_eq!(plane.coordinates(&couplings), (couplings.couplings[1], couplings.couplings[3]));
+        assert_eq!(couplings.couplings[0], 0.1);
+    }
+
+    #[test]
+    fn test_parallel_directions_are_rejected() {
+        let origin = Couplings { couplings: [0.; 3] };
+        let range = (-1., 1.);
+        assert!(Plane::new(origin.clone(), [[1., 2., 0.], [-2., -4., 0.]], range, range).is_none());
+        assert!(Plane::new(origin.clone(), [[1., 2., 0.], [0.; 3]], range, range).is_none());
+        assert!(Plane::new(origin, [[1., 2., 0.], [1., 2., 1E-3]], range, range).is_some());
+    }
+}
@@ -0,0 +1,170 @@
+use crate::model::{Couplings, Model};
+use crate::scanner::scanner::{SampleSpace, ScanStatistics};
+use crate::util::random::standard_normal;
+use crate::util::stability::FinalStabilityResult;
+
+// Proposals without a stable one after which the stable region is considered empty
+const MAX_PROPOSALS: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sampling {
+    // Uniform in the sample space
+    Uniform,
+    // Uniform in the part of the sample space that satisfies the stability condition of the model,
+    // by rejection of unstable proposals
+    StableRejection,
+    // The same distribution from a hit-and-run Markov chain, which takes this many moves between
+    // samples. The stable fraction is estimated from one independent uniform proposal per sample.
+    StableHitAndRun(usize),
+}
+
+// Draws couplings from the sample space, counting the proposals that are checked for stability
+pub struct Sampler<const N: usize> {
+    space: SampleSpace<N>,
+    sampling: Sampling,
+    ranges: Vec<(f64, f64)>,
+    // Current point of the hit-and-run chain
+    current: Option<Vec<f64>>,
+    max_proposals: u64,
+}
+impl<const N: usize> Sampler<N> {
+    pub fn new(space: SampleSpace<N>, sampling: Sampling) -> Self {
+        let ranges = space.ranges();
+        Self {
+            space,
+            sampling,
+            ranges,
+            current: None,
+            max_proposals: MAX_PROPOSALS,
+        }
+    }
+
+    // None if stable couplings are sampled and there are none in max_proposals proposals
+    pub fn sample(&mut self, model: &dyn Model<N>, statistics: &mut ScanStatistics) -> Option<Couplings<N>> {
+        match self.sampling {
+            Sampling::Uniform => Some(self.space.sample()),
+            Sampling::StableRejection => {
+                let coordinates = self.stable_proposal(model, statistics)?;
+                Some(self.space.couplings(&coordinates))
+            }
+            Sampling::StableHitAndRun(moves) => {
+                let mut current = match self.current.take() {
+                    Some(current) => {
+                        // Only counted towards the stable fraction
+                        self.propose(model, statistics);
+                        current
+                    }
+                    None => self.stable_proposal(model, statistics)?,
+                };
+                for _ in 0..moves {
+                    current = self.hit_and_run(model, current);
+                }
+                let couplings = self.space.couplings(&current);
+                self.current = Some(current);
+                Some(couplings)
+            }
+        }
+    }
+
+    fn uniform(&self) -> Vec<f64> {
+        self.ranges.iter().map(|&(min, max)| rand::random::<f64>() * (max - min) + min).collect()
+    }
+
+    fn is_stable(&self, model: &dyn Model<N>, coordinates: &[f64]) -> bool {
+        matches!(model.stability_condition(&self.space.couplings(coordinates)), FinalStabilityResult::Stable)
+    }
+
+    // Uniform proposal, returned if it is stable
+    fn propose(&self, model: &dyn Model<N>, statistics: &mut ScanStatistics) -> Option<Vec<f64>> {
+        let coordinates = self.uniform();
+        statistics.proposals += 1;
+        if !self.is_stable(model, &coordinates) {
+            return None;
+        }
+        statistics.stable_proposals += 1;
+        Some(coordinates)
+    }
+
+    fn stable_proposal(&self, model: &dyn Model<N>, statistics: &mut ScanStatistics) -> Option<Vec<f64>> {
+        (0..self.max_proposals).find_map(|_| self.propose(model, statistics))
+    }
+
+    // Move to a uniform point of the stable part of the chord through the current point in a random
+    // direction. The chord of the ranges is shrunk towards the current point after every unstable
+    // point, which leaves the uniform distribution invariant also for non-convex stable regions.
+    fn hit_and_run(&self, model: &dyn Model<N>, current: Vec<f64>) -> Vec<f64> {
+        // Directions are isotropic in units of the widths of the ranges, and fixed coordinates stay
+        let direction: Vec<f64> = self.ranges.iter().map(|(min, max)| (max - min) * standard_normal()).collect();
+        let (mut lower, mut upper) = (f64::NEG_INFINITY, f64::INFINITY);
+        for ((&x, &d), &(min, max)) in current.iter().zip(&direction).zip(&self.ranges) {
+            if d != 0. {
+                let (a, b) = ((min - x) / d, (max - x) / d);
+                lower = lower.max(a.min(b));
+                upper = upper.min(a.max(b));
+            }
+        }
+        if !lower.is_finite() || !upper.is_finite() || lower >= upper {
+            return current;
+        }
+        loop {
+            let t = lower + rand::random::<f64>() * (upper - lower);
+            let point: Vec<f64> = current.iter().zip(&direction).map(|(x, d)| x + t * d).collect();
+            if self.is_stable(model, &point) {
+                return point;
+            }
+            if t < 0. {
+                lower = t;
+            } else {
+                upper = t;
+            }
+            // The current point is stable, so the chord shrinks onto it at worst
+            if upper - lower < 1E-12 {
+                return current;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{allowed_violation, no_running, StubModel};
+
+    // Stable outside of the quadrant x, y <= 0, which is three quarters of [-1, 1]^2
+    const QUADRANT: StubModel<2> = StubModel {
+        beta_function: no_running,
+        stability_condition: |couplings| {
+            if couplings.couplings.iter().any(|&x| x > 0.) { FinalStabilityResult::Stable } else { allowed_violation() }
+        },
+    };
+
+    #[test]
+    fn test_stable_sampling() {
+        let space = SampleSpace::Ranges([(-1., 1.); 2]);
+        for sampling in [Sampling::StableRejection, Sampling::StableHitAndRun(5)] {
+            let mut sampler = Sampler::new(space.clone(), sampling);
+            let mut statistics = ScanStatistics::default();
+            let num_samples = 20000;
+            let mut mean = 0.;
+            for _ in 0..num_samples {
+                let couplings = sampler.sample(&QUADRANT, &mut statistics).unwrap();
+                assert!(matches!(QUADRANT.stability_condition(&couplings), FinalStabilityResult::Stable));
+                mean += couplings.couplings[0] / num_samples as f64;
+            }
+            // The mean of x over the stable region is 1/6
+            assert!((mean - 1. / 6.).abs() < 0.03, "{:?}: {}", sampling, mean);
+            let fraction = statistics.stable_fraction().unwrap();
+            assert!((fraction - 0.75).abs() < 0.02, "{:?}: {}", sampling, fraction);
+        }
+    }
+
+    #[test]
+    fn test_no_stable_couplings() {
+        let unstable = StubModel { stability_condition: |_| allowed_violation(), ..QUADRANT };
+        let mut sampler = Sampler::new(SampleSpace::Ranges([(-1., 1.); 2]), Sampling::StableRejection);
+        sampler.max_proposals = 1000;
+        let mut statistics = ScanStatistics::default();
+        assert!(sampler.sample(&unstable, &mut statistics).is_none());
+        assert_eq!((statistics.proposals, statistics.stable_fraction()), (1000, Some(0.)));
+    }
+}
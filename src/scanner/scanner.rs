@@ -1,46 +1,121 @@
 use std::marker::PhantomData;
 use crate::model::{Couplings, Model};
 use crate::scanner::consumer::ScanConsumer;
+use crate::scanner::parametrisation::Parametrisation;
+use crate::scanner::plane::Plane;
+use crate::scanner::sampler::{Sampler, Sampling};
+use crate::shared_running::SharedRunning;
 use crate::simulation::{IntegrationParameters, IntegrationResult, Integrator};
 use std::sync::mpsc::Sender;
+use std::sync::Arc;
 
 pub type CouplingRanges<const N: usize> = [(f64, f64); N];
 
+// Where samples are drawn uniformly from
+#[derive(Debug, Clone)]
+pub enum SampleSpace<const N: usize> {
+    Ranges(CouplingRanges<N>),
+    Plane(Plane<N>),
+    // Ranges of the coordinates of the parametrisation
+    Parametrised(Parametrisation<N>, CouplingRanges<N>),
+}
+impl<const N: usize> SampleSpace<N> {
+    pub fn sample(&self) -> Couplings<N> {
+        let coordinates: Vec<f64> = self.ranges().iter().map(|&(min, max)| rand::random::<f64>() * (max - min) + min).collect();
+        self.couplings(&coordinates)
+    }
+
+    // Ranges of the coordinates that are sampled uniformly
+    pub fn ranges(&self) -> Vec<(f64, f64)> {
+        match self {
+            SampleSpace::Ranges(ranges) | SampleSpace::Parametrised(_, ranges) => ranges.to_vec(),
+            SampleSpace::Plane(plane) => vec![plane.range_x, plane.range_y],
+        }
+    }
+
+    pub fn couplings(&self, coordinates: &[f64]) -> Couplings<N> {
+        match self {
+            SampleSpace::Ranges(_) => Couplings { couplings: coordinates.try_into().unwrap() },
+            SampleSpace::Plane(plane) => plane.couplings(coordinates[0], coordinates[1]),
+            SampleSpace::Parametrised(parametrisation, _) => parametrisation.couplings(coordinates.try_into().unwrap()),
+        }
+    }
+}
+
+// Outcomes that are counted instead of (or in addition to) being passed to the consumer
+#[derive(Debug, Clone, Default)]
+pub struct ScanStatistics {
+    pub invalid: u64,
+    pub diverged: u64,
+    // Independent uniform proposals checked for stability when sampling only stable couplings
+    pub proposals: u64,
+    pub stable_proposals: u64,
+    // Whether a scan stopped early for lack of stable couplings
+    pub no_stable_couplings: bool,
+}
+impl ScanStatistics {
+    pub fn merge(&mut self, other: &ScanStatistics) {
+        self.invalid += other.invalid;
+        self.diverged += other.diverged;
+        self.proposals += other.proposals;
+        self.stable_proposals += other.stable_proposals;
+        self.no_stable_couplings |= other.no_stable_couplings;
+    }
+
+    // Estimate of the fraction of the volume of the sample space that is stable
+    pub fn stable_fraction(&self) -> Option<f64> {
+        (self.proposals > 0).then(|| self.stable_proposals as f64 / self.proposals as f64)
+    }
+}
+
 pub struct Scanner<T: ScanConsumer<N>, const N: usize> {
-    coupling_ranges: CouplingRanges<N>,
+    sampler: Sampler<N>,
     integrator: Integrator<N>,
     
     consumer: PhantomData<T>
 }
 impl<T: ScanConsumer<N>, const N: usize> Scanner<T, N> {
     pub fn new(
-        coupling_ranges: CouplingRanges<N>,
+        space: SampleSpace<N>,
+        sampling: Sampling,
         params: IntegrationParameters,
         model: Box<dyn Model<N>>,
     ) -> Self {
+        let initial_couplings = space.sample();
         Self {
-            coupling_ranges,
-            integrator: Integrator::new(params, model, generate_couplings(coupling_ranges)),
+            sampler: Sampler::new(space, sampling),
+            integrator: Integrator::new(params, model, initial_couplings),
             
             consumer: PhantomData,
         }
     }
 
+    pub fn share_running(&mut self, running: Arc<SharedRunning>) {
+        self.integrator.share_running(running);
+    }
+
     pub fn scan(
         &mut self,
         num_samples: u64,
         consumer: &mut T,
         sender: Sender<u64>,
-    ) {
+    ) -> ScanStatistics {
         let mut i = 0;
-        let mut invalids = 0;
+        let mut statistics = ScanStatistics::default();
         while i < num_samples {
-            let couplings = generate_couplings(self.coupling_ranges);
+            let Some(couplings) = self.sampler.sample(self.integrator.model.as_ref(), &mut statistics) else {
+                statistics.no_stable_couplings = true;
+                break;
+            };
             self.integrator.reset(&couplings);
             let res = self.integrator.perform_full_integration();
-            if let IntegrationResult::Invalid = res {
-                invalids += 1;
-                continue;
+            match res {
+                IntegrationResult::Invalid => {
+                    statistics.invalid += 1;
+                    continue;
+                }
+                IntegrationResult::Diverged(_, _) => statistics.diverged += 1,
+                _ => {}
             }
             consumer.consume(couplings, res);
             i += 1;
@@ -49,19 +124,11 @@ impl<T: ScanConsumer<N>, const N: usize> Scanner<T, N> {
                 sender.send(1000).expect("Failed to send progress");
             }
         }
+        // The samples that were not drawn are counted as done
         sender
-            .send(num_samples % 1000)
+            .send(num_samples - i / 1000 * 1000)
             .expect("Failed to send progress");
-        
-        println!("Invalids: {}", invalids);
-    }
-}
 
-fn generate_couplings<const N: usize>(coupling_ranges: CouplingRanges<N>) -> Couplings<N> {
-    let mut couplings = [0.0; N];
-    for i in 0..N {
-        let (min, max) = coupling_ranges[i];
-        couplings[i] = rand::random::<f64>() * (max - min) + min;
+        statistics
     }
-    Couplings { couplings }
 }
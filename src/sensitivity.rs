@@ -0,0 +1,167 @@
+use crate::model::Couplings;
+use crate::simulation::Integrator;
+use crate::util::scalar::Scalar;
+use crate::util::stability::FinalStabilityResult;
+
+// The crossing into instability is searched within this many steps around the end point
+const CROSSING_BRACKET: f64 = 4.;
+const BISECTIONS: usize = 60;
+// Shift of the end point for the finite differences of the crossing, relative to the largest change
+// of a coupling in the last step
+const CROSSING_SHIFT: f64 = 0.1;
+
+// Linearised dependence of an integration on the initial couplings lambda_j(t0)
+#[derive(Debug, Clone)]
+pub struct Sensitivity<const N: usize> {
+    // d lambda_i / d lambda_j(t0) at the end point
+    pub couplings: [[f64; N]; N],
+    // d t_break / d lambda_j(t0) when the potential turned unstable
+    pub breaking_scale: Option<[f64; N]>,
+}
+impl<const N: usize> Sensitivity<N> {
+    pub fn breaking_scale_gradient_magnitude(&self) -> Option<f64> {
+        self.breaking_scale.map(|gradient| gradient.iter().map(|x| x * x).sum::<f64>().sqrt())
+    }
+
+    // Barbieri-Giudice measure max_i |d ln mu_break / d ln lambda_i(t0)| = max_i |lambda_i d t_break / d lambda_i|
+    pub fn fine_tuning(&self, initial_couplings: &Couplings<N>) -> Option<f64> {
+        let gradient = self.breaking_scale?;
+        Some(gradient.iter().zip(&initial_couplings.couplings).fold(0., |a, (d, l)| a.max((d * l).abs())))
+    }
+}
+
+pub(crate) fn identity<const N: usize, S: Scalar>() -> [[S; N]; N] {
+    std::array::from_fn(|i| std::array::from_fn(|j| S::from_f64(if i == j { 1. } else { 0. })))
+}
+
+impl<const N: usize, S: Scalar> Integrator<N, S> {
+    // Propagates the tangent of the flow through a step starting at the current couplings. This is
+    // exact for Euler steps and a first-order approximation of the variational equations
+    // d Phi / dt = J Phi for the other methods.
+    pub(crate) fn propagate_tangent(&mut self, step_size: f64) {
+        let Some(tangent) = self.tangent else {
+            return;
+        };
+        let jacobian = self.model.jacobian(&self.time_step.couplings);
+        let step = S::from_f64(step_size);
+        let loop_order = self.params.loop_order;
+        self.tangent = Some(std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                let mut change = S::from_f64(0.);
+                for (derivative, row) in jacobian[i].iter().zip(&tangent) {
+                    change += derivative.compute_to_order(loop_order) * row[j];
+                }
+                tangent[i][j] + step * change
+            })
+        }))
+    }
+
+    // Sensitivity at the current couplings if enabled, with the breaking scale for an integration
+    // that has just turned unstable
+    pub(crate) fn sensitivity(&self, broken: bool) -> Option<Sensitivity<N>> {
+        let tangent = self.tangent?;
+        let breaking_scale = if broken { self.breaking_scale_gradient(&tangent) } else { None };
+        Some(Sensitivity {
+            couplings: tangent.map(|row| row.map(|x| x.to_f64())),
+            breaking_scale,
+        })
+    }
+
+    // The boundary of stability is crossed on the straight continuation of the last step. Shifting
+    // the end point along every coupling moves the crossing, which gives d t_break / d lambda_k at
+    // the end point, transported back to t0 with the tangent.
+    fn breaking_scale_gradient(&self, tangent: &[[S; N]; N]) -> Option<[f64; N]> {
+        let (change, step_size) = self.previous_step?;
+        let end = self.time_step.couplings.couplings;
+        let shift = CROSSING_SHIFT * change.iter().fold(0_f64, |a, c| a.max(c.to_f64().abs()));
+        if shift == 0. || !shift.is_finite() {
+            return None;
+        }
+
+        let mut end_gradient = [0.; N];
+        for (k, gradient) in end_gradient.iter_mut().enumerate() {
+            let mut crossings = [0.; 2];
+            for (crossing, sign) in crossings.iter_mut().zip([1., -1.]) {
+                let mut shifted = end;
+                shifted[k] += S::from_f64(sign * shift);
+                *crossing = self.crossing(&shifted, &change)?;
+            }
+            *gradient = step_size * (crossings[0] - crossings[1]) / (2. * shift);
+        }
+        Some(std::array::from_fn(|j| (0..N).map(|k| end_gradient[k] * tangent[k][j].to_f64()).sum()))
+    }
+
+    // Fraction u of a step at which end + u * change turns unstable, by bisection
+    fn crossing(&self, end: &[S; N], change: &[S; N]) -> Option<f64> {
+        let unstable = |u: f64| {
+            let couplings = Couplings {
+                couplings: std::array::from_fn(|i| end[i] + S::from_f64(u) * change[i]),
+            };
+            !matches!(self.model.stability_condition(&couplings), FinalStabilityResult::Stable)
+        };
+        let (mut stable, mut unstable_end) = (-CROSSING_BRACKET, CROSSING_BRACKET);
+        if unstable(stable) || !unstable(unstable_end) {
+            return None;
+        }
+        for _ in 0..BISECTIONS {
+            let middle = 0.5 * (stable + unstable_end);
+            if unstable(middle) {
+                unstable_end = middle;
+            } else {
+                stable = middle;
+            }
+        }
+        Some(0.5 * (stable + unstable_end))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::gauge_theory::GaugeTheory;
+    use crate::simulation::{IntegrationParameters, IntegrationResult};
+    use crate::test_support::{stub_params, LINEAR_RUNNING};
+
+    fn params(initial_scale: f64, final_scale: f64) -> IntegrationParameters {
+        IntegrationParameters {
+            initial_scale,
+            final_scale,
+            max_relative_change: 0.1,
+            sensitivity: true,
+            ..stub_params()
+        }
+    }
+
+    #[test]
+    fn test_breaking_scale_gradient() {
+        let (l1, l2) = (0.5, 0.2);
+        let couplings = Couplings { couplings: [l1, l2] };
+        let mut integrator = Integrator::new(params(10., 0.), Box::new(LINEAR_RUNNING), couplings);
+        let IntegrationResult::Broken { end_point, .. } = integrator.perform_full_integration() else {
+            panic!("Expected a breaking");
+        };
+        let sensitivity = end_point.sensitivity.unwrap();
+        let expected = [[1., end_point.log_scale - 10.], [0., 1.]];
+        for (row, expected_row) in sensitivity.couplings.iter().zip(expected) {
+            assert!(row.iter().zip(expected_row).all(|(a, b)| (a - b).abs() < 1E-9));
+        }
+        let [d1, d2] = sensitivity.breaking_scale.unwrap();
+        assert!((d1 + 1. / l2).abs() < 1E-6 && (d2 - l1 / (l2 * l2)).abs() < 1E-6);
+    }
+
+    #[test]
+    fn test_tangent_of_gauge_running() {
+        // g(t)^-2 = g0^-2 - 2 b (t - t0) / (16 pi^2), so d g / d g0 = (g / g0)^3
+        let g0 = 0.5;
+        let model = GaugeTheory { coefficients: [-7.] };
+        let mut integrator = Integrator::new(params(40., 5.), Box::new(model), Couplings { couplings: [g0] });
+        let IntegrationResult::Unbroken(end_point) = integrator.perform_full_integration() else {
+            panic!("Expected no breaking");
+        };
+        let g = end_point.couplings.couplings[0];
+        let sensitivity = end_point.sensitivity.unwrap();
+        // The Euler steps are accurate to O(step size)
+        assert!((sensitivity.couplings[0][0] / (g / g0).powi(3) - 1.).abs() < 5E-3);
+        assert!(sensitivity.breaking_scale.is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use crate::model::{Couplings, LoopOrder, LoopTerms, Model};
+use crate::simulation::{IntegrationMethod, IntegrationParameters};
+use crate::util::scalar::Scalar;
+
+// Running of the couplings that a model declares independent at the loop order, computed once and
+// shared by every integration with the same parameters that starts with the same values of these
+// couplings. Such integrations only evaluate Model::dependent_beta_function and take the loop terms
+// of the independent couplings from here, which reproduces the unshared integration exactly.
+//
+// The three-loop terms of the independent couplings depend on the other couplings, as the quartics
+// do for the gauge coupling of MainModel. They are left at zero here, so the running is only shared
+// with explicit Euler steps below three loops and with perturbativity criteria that do not read
+// the three-loop terms.
+#[derive(Debug)]
+pub struct SharedRunning<S: Scalar = f64> {
+    pub indices: Vec<usize>,
+    initial_values: Vec<S>,
+    // Loop terms of the independent couplings in every step, in the order of indices, up to the end
+    // of the integration or to the step in which they stop being finite
+    loop_terms: Vec<LoopTerms<S>>,
+    initial_scale: f64,
+    step_size: f64,
+    loop_order: LoopOrder,
+}
+impl<S: Scalar> SharedRunning<S> {
+    // None if the model has no independent couplings at the loop order of the parameters or the
+    // parameters rule out sharing their running
+    pub fn new<const N: usize>(
+        model: &dyn Model<N, S>,
+        params: &IntegrationParameters<S>,
+        couplings: &Couplings<N, S>,
+    ) -> Option<Self> {
+        let indices = model.independent_couplings(params.loop_order).to_vec();
+        if indices.is_empty()
+            || params.method != IntegrationMethod::Euler
+            || params.loop_order == LoopOrder::Three
+            || params.perturbativity.reads_three_loops()
+        {
+            return None;
+        }
+        let step_size = params.step_size();
+        let step = S::from_f64(step_size);
+        let zero = S::from_f64(0.);
+        // The other couplings do not enter the terms below three loops
+        let mut block = Couplings {
+            couplings: std::array::from_fn(|i| if indices.contains(&i) { couplings.couplings[i] } else { zero }),
+        };
+        let mut loop_terms = Vec::with_capacity(params.num_steps * indices.len());
+        for _ in 0..params.num_steps {
+            let beta_functions = model.beta_function(&block);
+            for &i in &indices {
+                let terms = beta_functions[i].loop_terms();
+                loop_terms.push(LoopTerms { three: zero, ..terms });
+                block.couplings[i] += terms.to_order(params.loop_order) * step;
+            }
+            if indices.iter().any(|&i| !block.couplings[i].is_finite()) {
+                break;
+            }
+        }
+        Some(Self {
+            initial_values: indices.iter().map(|&i| couplings.couplings[i]).collect(),
+            indices,
+            loop_terms,
+            initial_scale: params.initial_scale,
+            step_size,
+            loop_order: params.loop_order,
+        })
+    }
+
+    // Whether an integration with the parameters that starts from the couplings at the initial scale
+    // can take the running from here
+    pub(crate) fn applies_to<const N: usize>(&self, params: &IntegrationParameters<S>, couplings: &Couplings<N, S>) -> bool {
+        params.initial_scale == self.initial_scale
+            && params.step_size() == self.step_size
+            && params.loop_order == self.loop_order
+            && params.method == IntegrationMethod::Euler
+            && !params.perturbativity.reads_three_loops()
+            && self.indices.iter().zip(&self.initial_values).all(|(&i, &value)| couplings.couplings[i] == value)
+    }
+
+    // Loop terms of the independent couplings in the given step, in the order of indices
+    pub(crate) fn loop_terms(&self, step: usize) -> Option<&[LoopTerms<S>]> {
+        let count = self.indices.len();
+        self.loop_terms.get(step * count..(step + 1) * count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::main_model::MainModel;
+    use crate::simulation::Integrator;
+    use crate::test_support::test_params;
+    use crate::util::perturbativity::{LandauPole, Perturbativity};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_shared_gauge_running() {
+        let couplings = Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        };
+        let params = IntegrationParameters { loop_order: LoopOrder::Two, ..test_params() };
+        let mut integrator = Integrator::new(params.clone(), Box::new(MainModel), couplings.clone());
+        let expected = integrator.perform_full_integration();
+
+        let running = SharedRunning::new(&MainModel, &params, &couplings).unwrap();
+        assert_eq!(running.indices, [0]);
+        integrator.share_running(Arc::new(running));
+        integrator.reset(&couplings);
+        let result = integrator.perform_full_integration();
+        assert!(integrator.shared_step.is_some());
+        assert_eq!(result.outcome(), expected.outcome());
+        assert_eq!(result.event_scale(), expected.event_scale());
+        assert_eq!(
+            result.end_point().map(|end_point| end_point.couplings.couplings),
+            expected.end_point().map(|end_point| end_point.couplings.couplings)
+        );
+
+        // Other gauge couplings are integrated as before
+        let mut other = couplings.clone();
+        other.couplings[0] = 0.4;
+        integrator.reset(&other);
+        integrator.perform_full_integration();
+        assert_eq!(integrator.shared_step, None);
+
+        // The quartics feed back into the gauge coupling at three loops, and the Landau pole criterion
+        // reads the three-loop terms
+        assert!(SharedRunning::new(&MainModel, &test_params(), &couplings).is_none());
+        let landau_pole = IntegrationParameters {
+            perturbativity: Perturbativity::LandauPole(LandauPole { min_distance: 1. }),
+            ..params
+        };
+        assert!(SharedRunning::new(&MainModel, &landau_pole, &couplings).is_none());
+    }
+}
@@ -0,0 +1,204 @@
+use crate::model::Couplings;
+use crate::simulation::{Divergence, Integrator};
+use crate::util::linear_algebra::{solve_linear, Matrix};
+
+// Relative size of the finite-difference steps of the Jacobian
+const DIFFERENCE_STEP: f64 = 1E-6;
+// Newton steps are halved at most this many times while they do not decrease the residual
+const MAX_DAMPING: usize = 20;
+
+// Mixed boundary conditions: the couplings in low_scale_values are fixed at low_scale, all others at
+// params.initial_scale of the integrator. Conditions at low_scale constrain the running of the
+// model itself, continued through any breaking or loss of perturbativity above low_scale.
+#[derive(Debug, Clone)]
+pub struct ShootingParameters {
+    pub low_scale: f64,
+    // (index of the coupling, value at low_scale)
+    pub low_scale_values: Vec<(usize, f64)>,
+    // Largest absolute deviation from the low_scale values of a solution
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShootingSolution<const N: usize> {
+    // Couplings at params.initial_scale that satisfy all boundary conditions
+    pub couplings: Couplings<N>,
+    pub iterations: usize,
+    // Largest deviation from the low_scale values before every iteration and for the solution
+    pub residuals: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ShootingFailure {
+    // A trajectory of the iteration ran into a pole
+    Diverged(Divergence),
+    // The low_scale values do not depend (independently) on the couplings adjusted at initial_scale
+    SingularJacobian(usize),
+    // No step decreases the residual any further, or max_iterations has been reached
+    NoConvergence(Vec<f64>),
+    // A coupling index of low_scale_values is out of range or appears more than once
+    InvalidIndex(usize),
+}
+
+impl<const N: usize> Integrator<N> {
+    // Finds the couplings at params.initial_scale that reach the low_scale values, starting from
+    // initial_guess, which also provides the couplings that are fixed at initial_scale. Uses Newton's
+    // method with a finite-difference Jacobian and damped steps.
+    pub fn solve_boundary_value_problem(
+        &mut self,
+        initial_guess: &Couplings<N>,
+        shooting: &ShootingParameters,
+    ) -> Result<ShootingSolution<N>, ShootingFailure> {
+        let indices: Vec<usize> = shooting.low_scale_values.iter().map(|&(index, _)| index).collect();
+        for (position, &index) in indices.iter().enumerate() {
+            if index >= N || indices[..position].contains(&index) {
+                return Err(ShootingFailure::InvalidIndex(index));
+            }
+        }
+        let mut couplings = initial_guess.clone();
+        let mut residual = self.shooting_residual(&couplings, shooting).map_err(ShootingFailure::Diverged)?;
+        let mut residuals = vec![max_norm(&residual)];
+
+        for iteration in 0..shooting.max_iterations {
+            if max_norm(&residual) <= shooting.tolerance {
+                return Ok(ShootingSolution {
+                    couplings,
+                    iterations: iteration,
+                    residuals,
+                });
+            }
+
+            // Jacobian of the residual with respect to the adjusted couplings, column by column
+            let mut jacobian = Matrix::zeros(indices.len(), indices.len());
+            for (column, &index) in indices.iter().enumerate() {
+                let step = DIFFERENCE_STEP * couplings.couplings[index].abs().max(1.);
+                let mut shifted = couplings.clone();
+                shifted.couplings[index] += step;
+                let shifted_residual = self.shooting_residual(&shifted, shooting).map_err(ShootingFailure::Diverged)?;
+                for (row, (a, b)) in shifted_residual.iter().zip(&residual).enumerate() {
+                    jacobian[(row, column)] = (a - b) / step;
+                }
+            }
+            let negative_residual: Vec<f64> = residual.iter().map(|r| -r).collect();
+            let newton_step = solve_linear(jacobian, negative_residual).map_err(ShootingFailure::SingularJacobian)?;
+
+            let mut damping = 1.;
+            let mut accepted = None;
+            for _ in 0..MAX_DAMPING {
+                let mut candidate = couplings.clone();
+                for (&index, step) in indices.iter().zip(&newton_step) {
+                    candidate.couplings[index] += damping * step;
+                }
+                // A trajectory that diverges counts as overshooting
+                if let Ok(candidate_residual) = self.shooting_residual(&candidate, shooting)
+                    && max_norm(&candidate_residual) < max_norm(&residual)
+                {
+                    accepted = Some((candidate, candidate_residual));
+                    break;
+                }
+                damping /= 2.;
+            }
+            let Some((candidate, candidate_residual)) = accepted else {
+                return Err(ShootingFailure::NoConvergence(residuals));
+            };
+            couplings = candidate;
+            residual = candidate_residual;
+            residuals.push(max_norm(&residual));
+        }
+
+        if max_norm(&residual) <= shooting.tolerance {
+            Ok(ShootingSolution {
+                couplings,
+                iterations: shooting.max_iterations,
+                residuals,
+            })
+        } else {
+            Err(ShootingFailure::NoConvergence(residuals))
+        }
+    }
+
+    fn shooting_residual(&mut self, couplings: &Couplings<N>, shooting: &ShootingParameters) -> Result<Vec<f64>, Divergence> {
+        self.reset(couplings);
+        self.run_to(shooting.low_scale)?;
+        let result = shooting
+            .low_scale_values
+            .iter()
+            .map(|&(index, value)| self.time_step.couplings.couplings[index] - value)
+            .collect();
+        self.reset(couplings);
+        Ok(result)
+    }
+}
+
+fn max_norm(values: &[f64]) -> f64 {
+    values.iter().fold(0., |a, b| a.max(b.abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::so10;
+    use crate::test_support::test_params;
+    use crate::util::constants::PI_4_2;
+
+    fn integrator() -> Integrator<3> {
+        let params = test_params();
+        let guess = Couplings { couplings: [0.5; 3] };
+        Integrator::new(params, Box::new(so10::STANDARD_MODEL_GAUGE_THEORY), guess)
+    }
+
+    #[test]
+    fn test_shooting_matches_one_loop_running() {
+        let low_scale = 91.2_f64.ln();
+        let targets = [1.22, 0.65, 0.46];
+        let shooting = ShootingParameters {
+            low_scale,
+            // The strong coupling is fixed at the Planck scale
+            low_scale_values: vec![(1, targets[1]), (2, targets[2])],
+            tolerance: 1E-10,
+            max_iterations: 20,
+        };
+        let mut integrator = integrator();
+        let guess = Couplings { couplings: [0.5; 3] };
+        let solution = integrator.solve_boundary_value_problem(&guess, &shooting).unwrap();
+        assert_eq!(solution.couplings.couplings[0], 0.5);
+
+        let b = so10::STANDARD_MODEL_GAUGE_THEORY.coefficients;
+        let distance = low_scale - integrator.params.initial_scale;
+        for i in 1..3 {
+            // 1/g^2(t) = 1/g^2(t0) - 2 b (t - t0) / (16 pi^2)
+            let expected = (targets[i].powi(-2) + 2. * b[i] * distance / PI_4_2).powf(-0.5);
+            assert!((solution.couplings.couplings[i] - expected).abs() < 1E-3 * expected);
+        }
+        assert!(solution.residuals.last().unwrap() <= &1E-10);
+    }
+
+    #[test]
+    fn test_shooting_fails_without_solution() {
+        // Without asymptotic freedom the coupling at low_scale is bounded from above
+        let shooting = ShootingParameters {
+            low_scale: 91.2_f64.ln(),
+            low_scale_values: vec![(2, 10.)],
+            tolerance: 1E-10,
+            max_iterations: 50,
+        };
+        let guess = Couplings { couplings: [0.5; 3] };
+        assert!(integrator().solve_boundary_value_problem(&guess, &shooting).is_err());
+    }
+
+    #[test]
+    fn test_shooting_rejects_invalid_indices() {
+        let guess = Couplings { couplings: [0.5; 3] };
+        for (low_scale_values, invalid) in [(vec![(3, 0.5)], 3), (vec![(1, 0.6), (2, 0.5), (1, 0.7)], 1)] {
+            let shooting = ShootingParameters {
+                low_scale: 91.2_f64.ln(),
+                low_scale_values,
+                tolerance: 1E-10,
+                max_iterations: 20,
+            };
+            let result = integrator().solve_boundary_value_problem(&guess, &shooting);
+            assert!(matches!(result, Err(ShootingFailure::InvalidIndex(index)) if index == invalid));
+        }
+    }
+}
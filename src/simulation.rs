@@ -1,40 +1,230 @@
-use crate::model::{Couplings, Model, TimeStep};
-use crate::util::perturbativity::check_perturbativity;
-use crate::util::stability::{FinalStabilityResult, StabilityResult};
+use crate::model::{Couplings, LoopOrder, LoopTerms, Model, TimeStep};
+use crate::sensitivity::{identity, Sensitivity};
+use crate::shared_running::SharedRunning;
+use crate::util::effective_potential::GildenerWeinberg;
+use crate::util::interval::Precision;
+use crate::util::linear_algebra::solve_array;
+use crate::util::perturbativity::{Perturbativity, PerturbativityCriterion, PerturbativityViolation, TotalLoopRatio};
+use crate::util::scalar::Scalar;
+use crate::util::stability::{Certified, FinalStabilityResult, StabilityResult, StabilityViolation};
+use std::sync::Arc;
+
+// Newton iterations of the implicit methods stop once corrections are below this multiple of the
+// machine epsilon, relative to max(|coupling|, 1)
+const NEWTON_TOLERANCE: f64 = 64.;
+const MAX_NEWTON_ITERATIONS: usize = 50;
 
 #[derive(Debug, Clone)]
-pub struct IntegrationParameters {
+pub struct IntegrationParameters<S: Scalar = f64> {
     pub initial_scale: f64,
     pub final_scale: f64,
     pub num_steps: usize,
+    // Decide stability with interval arithmetic, falling back to higher precision and finally
+    // to plain f64 when the point is too close to the stability boundary
+    pub certified_stability: bool,
+    pub perturbativity: Perturbativity<S>,
+    // Largest change of a coupling in a single step, relative to max(|coupling|, 1), before the
+    // trajectory is considered to run into a pole
+    pub max_relative_change: f64,
+    // Analyse the one-loop effective potential along the flat direction of allowed breakings
+    pub gildener_weinberg: bool,
+    // Beta functions are truncated after this loop order
+    pub loop_order: LoopOrder,
+    pub method: IntegrationMethod,
+    // Integrate the dependence on the initial couplings along with them, see Sensitivity
+    pub sensitivity: bool,
+}
+
+impl<S: Scalar> IntegrationParameters<S> {
+    pub fn step_size(&self) -> f64 {
+        (self.final_scale - self.initial_scale) / self.num_steps as f64
+    }
+}
+
+// MainModel from the Planck scale to 1e11 in 10^6 explicit Euler steps at three loops
+impl<S: Scalar> Default for IntegrationParameters<S> {
+    fn default() -> Self {
+        Self {
+            initial_scale: 1.22E19_f64.ln(),
+            final_scale: 1.0E11_f64.ln(),
+            num_steps: 1000000,
+            certified_stability: false,
+            perturbativity: Perturbativity::TotalLoopRatio(TotalLoopRatio { threshold: 0.1 }),
+            max_relative_change: 0.01,
+            gildener_weinberg: false,
+            loop_order: LoopOrder::Three,
+            method: IntegrationMethod::Euler,
+            sensitivity: false,
+        }
+    }
+}
+
+// Steps of the integration. The implicit methods use the Jacobian of the model and stay stable
+// for stiff running close to poles, where the explicit Euler step needs very small steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrationMethod {
+    Euler,
+    // Two-stage L-stable Rosenbrock method of second order (ROS2)
+    Rosenbrock,
+    // Implicit midpoint rule, solved with Newton's method
+    ImplicitMidpoint,
+    // Second-order backward differentiation, started with an implicit Euler step
+    Bdf2,
+}
+
+#[derive(Debug, Clone)]
+pub enum Divergence {
+    NonFinite(usize),
+    Pole(usize),
+    // The equations of an implicit step have no solution close to the current couplings
+    Unsolvable,
 }
 
 enum IntegrationStepResult {
     Continue,
-    Stability(FinalStabilityResult),
-    Perturbativity,
+    Divergence(Divergence),
+    // With the violation that decided a certified result
+    Stability(FinalStabilityResult, Option<StabilityViolation>),
+    Perturbativity(PerturbativityViolation),
+}
+
+// Where the integration stopped, at the event or at final_scale
+#[derive(Debug, Clone)]
+pub struct EndPoint<const N: usize, S: Scalar = f64> {
+    // Scale of the couplings, without the offset applied to the reported scales of events
+    pub log_scale: f64,
+    pub couplings: Couplings<N, S>,
+    pub steps: usize,
+    // If enabled in the integration parameters
+    pub sensitivity: Option<Sensitivity<N>>,
+}
+
+pub enum IntegrationResult<const N: usize, S: Scalar = f64> {
+    Unbroken(EndPoint<N, S>),
+    InitiallyUnstable,
+    PerturbativityViolated(f64, PerturbativityViolation, EndPoint<N, S>),
+    // The first violated condition, which determines the outcome, all violated conditions and, if
+    // enabled, the Gildener-Weinberg analysis along the flat direction of the first one
+    Broken {
+        log_scale: f64,
+        result: FinalStabilityResult,
+        violations: Vec<StabilityViolation>,
+        gildener_weinberg: Option<GildenerWeinberg>,
+        end_point: EndPoint<N, S>,
+    },
+    Diverged(f64, Divergence),
+    Invalid,
+}
+
+impl<const N: usize, S: Scalar> IntegrationResult<N, S> {
+    pub fn outcome(&self) -> Outcome {
+        match self {
+            IntegrationResult::Unbroken(_) => Outcome::Unbroken,
+            IntegrationResult::InitiallyUnstable => Outcome::InitiallyUnstable,
+            IntegrationResult::PerturbativityViolated(_, _, _) => Outcome::PerturbativityViolated,
+            IntegrationResult::Broken { result: FinalStabilityResult::UnstableAllowed(_), .. } => Outcome::BrokenAllowed,
+            IntegrationResult::Broken { .. } => Outcome::BrokenDisallowed,
+            IntegrationResult::Diverged(_, _) => Outcome::Diverged,
+            IntegrationResult::Invalid => Outcome::Invalid,
+        }
+    }
+
+    // Reported scale of the event that ended the integration
+    pub fn event_scale(&self) -> Option<f64> {
+        match self {
+            IntegrationResult::PerturbativityViolated(scale, _, _)
+            | IntegrationResult::Broken { log_scale: scale, .. }
+            | IntegrationResult::Diverged(scale, _) => Some(*scale),
+            _ => None,
+        }
+    }
+
+    pub fn end_point(&self) -> Option<&EndPoint<N, S>> {
+        match self {
+            IntegrationResult::Unbroken(end_point)
+            | IntegrationResult::PerturbativityViolated(_, _, end_point)
+            | IntegrationResult::Broken { end_point, .. } => Some(end_point),
+            _ => None,
+        }
+    }
 }
 
-pub enum IntegrationResult {
+// Outcome class of an integration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Unbroken,
     InitiallyUnstable,
-    PerturbativityViolated(f64),
-    Broken(f64, FinalStabilityResult),
+    PerturbativityViolated,
+    BrokenAllowed,
+    BrokenDisallowed,
+    Diverged,
     Invalid,
 }
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Unbroken => "unbroken",
+            Outcome::InitiallyUnstable => "initially_unstable",
+            Outcome::PerturbativityViolated => "perturbativity_violated",
+            Outcome::BrokenAllowed => "broken_allowed",
+            Outcome::BrokenDisallowed => "broken_disallowed",
+            Outcome::Diverged => "diverged",
+            Outcome::Invalid => "invalid",
+        }
+    }
+}
+
+// The same initial couplings integrated at every loop order up to the highest one the model knows
+#[derive(Debug, Clone)]
+pub struct LoopOrderRun {
+    pub loop_order: LoopOrder,
+    // Whether the beta functions of all couplings are known to this order
+    pub complete: bool,
+    pub outcome: Outcome,
+    pub event_scale: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoopOrderComparison {
+    pub runs: Vec<LoopOrderRun>,
+}
+impl LoopOrderComparison {
+    pub fn outcomes_agree(&self) -> bool {
+        self.runs.windows(2).all(|runs| runs[0].outcome == runs[1].outcome)
+    }
+
+    // Difference between the largest and smallest breaking scale of the runs that broke
+    pub fn breaking_scale_spread(&self) -> Option<f64> {
+        let scales = self
+            .runs
+            .iter()
+            .filter(|run| matches!(run.outcome, Outcome::BrokenAllowed | Outcome::BrokenDisallowed))
+            .filter_map(|run| run.event_scale);
+        let (min, max) = scales.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), scale| (min.min(scale), max.max(scale)));
+        (min <= max).then_some(max - min)
+    }
+}
 
-pub struct Integrator<const N: usize> {
-    pub params: IntegrationParameters,
-    pub model: Box<dyn Model<N>>,
-    pub time_step: TimeStep<N>,
+pub struct Integrator<const N: usize, S: Scalar = f64> {
+    pub params: IntegrationParameters<S>,
+    pub model: Box<dyn Model<N, S>>,
+    pub time_step: TimeStep<N, S>,
+    // Change of the couplings in the previous step and its step size, for multistep methods
+    pub(crate) previous_step: Option<([S; N], f64)>,
+    // d lambda_i / d lambda_j(t0) if the sensitivity is enabled
+    pub(crate) tangent: Option<[[S; N]; N]>,
+    // Precomputed running of independent couplings and the step of the current integration within it
+    shared_running: Option<Arc<SharedRunning<S>>>,
+    pub(crate) shared_step: Option<usize>,
 }
-impl<const N: usize> Integrator<N> {
+impl<const N: usize, S: Scalar> Integrator<N, S> {
     pub fn new(
-        params: IntegrationParameters,
-        model: Box<dyn Model<N>>,
-        initial_couplings: Couplings<N>,
+        params: IntegrationParameters<S>,
+        model: Box<dyn Model<N, S>>,
+        initial_couplings: Couplings<N, S>,
     ) -> Self {
         let initial_scale = params.initial_scale;
+        let tangent = params.sensitivity.then(identity);
         Self {
             params,
             model,
@@ -42,58 +232,372 @@ impl<const N: usize> Integrator<N> {
                 log_scale: initial_scale,
                 couplings: initial_couplings,
             },
+            previous_step: None,
+            tangent,
+            shared_running: None,
+            shared_step: None,
         }
     }
 
-    fn perform_integration_step(&mut self) -> IntegrationStepResult {
-        let beta_functions = self.model.beta_function(&self.time_step.couplings);
+    pub(crate) fn step_size(&self) -> f64 {
+        self.params.step_size()
+    }
 
-        let step_size =
-            (self.params.final_scale - self.params.initial_scale) / self.params.num_steps as f64;
+    // Full integrations that start with the initial values of the shared running take the running
+    // of the independent couplings from it
+    pub fn share_running(&mut self, running: Arc<SharedRunning<S>>) {
+        self.shared_running = Some(running);
+    }
 
-        // Update the couplings based on the beta functions
-        for i in 0..N {
-            self.time_step.couplings.couplings[i] += beta_functions[i].compute() * step_size;
+    // Update the couplings based on the beta functions at the loop order of the parameters
+    pub(crate) fn advance(&mut self, derivative: [S; N], step_size: f64) -> Option<Divergence> {
+        let changes = match self.changes(derivative, step_size) {
+            Ok(changes) => changes,
+            Err(divergence) => return Some(divergence),
+        };
+        self.propagate_tangent(step_size);
+        for (coupling, &change) in self.time_step.couplings.couplings.iter_mut().zip(&changes) {
+            *coupling += change;
         }
         self.time_step.log_scale += step_size;
+        self.previous_step = Some((changes, step_size));
+
+        self.detect_divergence(&changes)
+    }
+
+    fn changes(&self, derivative: [S; N], step_size: f64) -> Result<[S; N], Divergence> {
+        let step = S::from_f64(step_size);
+        let couplings = self.time_step.couplings.couplings;
+        match self.params.method {
+            IntegrationMethod::Euler => Ok(derivative.map(|d| d * step)),
+            IntegrationMethod::Rosenbrock => {
+                let gamma = 1. + 0.5_f64.sqrt();
+                let matrix = self.step_matrix(gamma * step_size);
+                let k1 = solve_array(matrix, derivative).map_err(|_| Divergence::Unsolvable)?;
+                let shifted = self.derivative(&std::array::from_fn(|i| couplings[i] + step * k1[i]));
+                let k2 = solve_array(matrix, std::array::from_fn(|i| shifted[i] - S::from_f64(2.) * k1[i]))
+                    .map_err(|_| Divergence::Unsolvable)?;
+                Ok(std::array::from_fn(|i| step * (S::from_f64(1.5) * k1[i] + S::from_f64(0.5) * k2[i])))
+            }
+            IntegrationMethod::ImplicitMidpoint => {
+                // changes = h f(y + changes / 2)
+                let half = S::from_f64(0.5);
+                self.solve_implicit(derivative.map(|d| d * step), 0.5 * step_size, |changes| {
+                    let midpoint = self.derivative(&std::array::from_fn(|i| couplings[i] + half * changes[i]));
+                    std::array::from_fn(|i| changes[i] - step * midpoint[i])
+                })
+            }
+            IntegrationMethod::Bdf2 => {
+                // changes = previous / 3 + 2 h f(y + changes) / 3, or implicit Euler after a change of
+                // the step size
+                let (weight, history) = match self.previous_step {
+                    Some((previous, previous_size)) if previous_size == step_size => {
+                        (2. / 3., previous.map(|p| p / 3.))
+                    }
+                    _ => (1., [S::from_f64(0.); N]),
+                };
+                let factor = S::from_f64(weight) * step;
+                self.solve_implicit(derivative.map(|d| d * step), weight * step_size, |changes| {
+                    let end = self.derivative(&std::array::from_fn(|i| couplings[i] + changes[i]));
+                    std::array::from_fn(|i| changes[i] - history[i] - factor * end[i])
+                })
+            }
+        }
+    }
 
-        let stability_result = self.model.stability_condition(&self.time_step.couplings);
+    // Beta functions at the loop order of the parameters
+    fn derivative(&self, couplings: &[S; N]) -> [S; N] {
+        let beta_functions = self.model.beta_function(&Couplings { couplings: *couplings });
+        std::array::from_fn(|i| beta_functions[i].compute_to_order(self.params.loop_order))
+    }
+
+    // 1 - factor * J at the current couplings
+    fn step_matrix(&self, factor: f64) -> [[S; N]; N] {
+        let jacobian = self.model.jacobian(&self.time_step.couplings);
+        let factor = S::from_f64(factor);
+        std::array::from_fn(|i| {
+            std::array::from_fn(|j| {
+                let diagonal = S::from_f64(if i == j { 1. } else { 0. });
+                diagonal - factor * jacobian[i][j].compute_to_order(self.params.loop_order)
+            })
+        })
+    }
+
+    // Newton's method for residual(changes) = 0 with residual' ~ 1 - factor * J, the Jacobian kept
+    // at the start of the step
+    fn solve_implicit(
+        &self,
+        mut changes: [S; N],
+        factor: f64,
+        residual: impl Fn(&[S; N]) -> [S; N],
+    ) -> Result<[S; N], Divergence> {
+        let matrix = self.step_matrix(factor);
+        let tolerance = S::from_f64(NEWTON_TOLERANCE * S::EPSILON);
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let correction = solve_array(matrix, residual(&changes).map(|r| -r)).map_err(|_| Divergence::Unsolvable)?;
+            let mut converged = true;
+            for (i, (change, &delta)) in changes.iter_mut().zip(&correction).enumerate() {
+                *change += delta;
+                let coupling = self.time_step.couplings.couplings[i].abs();
+                let magnitude = if coupling > S::from_f64(1.) { coupling } else { S::from_f64(1.) };
+                if delta.is_nan() || delta.abs() > tolerance * magnitude {
+                    converged = false;
+                }
+            }
+            if converged {
+                return Ok(changes);
+            }
+        }
+        Err(Divergence::Unsolvable)
+    }
+
+    fn perform_integration_step(&mut self) -> IntegrationStepResult {
+        let step_size = self.step_size();
+        let shared = self.shared_step.zip(self.shared_running.as_ref()).and_then(|(step, running)| {
+            Some((&running.indices, running.loop_terms(step)?))
+        });
+        let beta_functions = if shared.is_some() {
+            self.model.dependent_beta_function(&self.time_step.couplings)
+        } else {
+            self.model.beta_function(&self.time_step.couplings)
+        };
+        let mut loop_terms = beta_functions.map(|beta_function| beta_function.loop_terms());
+        if let Some((indices, terms)) = shared {
+            for (&i, &terms) in indices.iter().zip(terms) {
+                loop_terms[i] = terms;
+            }
+        }
+        self.shared_step = self.shared_step.filter(|_| shared.is_some()).map(|step| step + 1);
+
+        // The derivative and the sums the perturbativity criteria need in a single pass
+        let mut derivative = [S::from_f64(0.); N];
+        let mut sums = LoopTerms::zero();
+        for (i, terms) in loop_terms.iter().enumerate() {
+            derivative[i] = terms.to_order(self.params.loop_order);
+            sums.add_abs(terms);
+        }
+
+        if let Some(divergence) = self.advance(derivative, step_size) {
+            return IntegrationStepResult::Divergence(divergence);
+        }
+
+        let (stability_result, certified_violation) = self.evaluate_stability();
         let FinalStabilityResult::Stable = stability_result else {
-            return IntegrationStepResult::Stability(stability_result);
+            return IntegrationStepResult::Stability(stability_result, certified_violation);
         };
 
-        if !check_perturbativity(beta_functions, 0.1) {
-            return IntegrationStepResult::Perturbativity;
+        if let Some(violation) = self.params.perturbativity.check(
+            &self.time_step.couplings.couplings,
+            &loop_terms,
+            &sums,
+            step_size,
+        ) {
+            return IntegrationStepResult::Perturbativity(violation);
         }
 
         IntegrationStepResult::Continue
     }
 
-    pub fn perform_full_integration(&mut self) -> IntegrationResult {
+    fn detect_divergence(&self, changes: &[S; N]) -> Option<Divergence> {
+        let one = S::from_f64(1.);
+        let max_relative_change = S::from_f64(self.params.max_relative_change);
+        for (i, (&coupling, &change)) in self.time_step.couplings.couplings.iter().zip(changes).enumerate() {
+            if !coupling.is_finite() {
+                return Some(Divergence::NonFinite(i));
+            }
+            let previous = (coupling - change).abs();
+            let magnitude = if previous > one { previous } else { one };
+            if change.abs() > max_relative_change * magnitude {
+                return Some(Divergence::Pole(i));
+            }
+        }
+        None
+    }
+
+    fn evaluate_stability(&self) -> (FinalStabilityResult, Option<StabilityViolation>) {
+        if self.params.certified_stability {
+            for precision in [Precision::Double, Precision::DoubleDouble] {
+                if let Certified::Decided(violation) = self
+                    .model
+                    .certified_stability_condition(&self.time_step.couplings, precision)
+                {
+                    let result = violation.as_ref().map_or(FinalStabilityResult::Stable, StabilityViolation::final_result);
+                    return (result, violation);
+                }
+            }
+        }
+        (self.model.stability_condition(&self.time_step.couplings), None)
+    }
+
+    fn end_point(&self, steps: usize, broken: bool) -> EndPoint<N, S> {
+        EndPoint {
+            log_scale: self.time_step.log_scale,
+            couplings: self.time_step.couplings.clone(),
+            steps,
+            sensitivity: self.sensitivity(broken),
+        }
+    }
+
+    pub fn perform_full_integration(&mut self) -> IntegrationResult<N, S> {
+        let at_start = self.time_step.log_scale == self.params.initial_scale;
+        self.shared_step = self
+            .shared_running
+            .as_ref()
+            .filter(|running| at_start && running.applies_to(&self.params, &self.time_step.couplings))
+            .map(|_| 0);
         for i in 0..self.params.num_steps {
             // println!("Couplings {}: {:?}", i, self.time_step.couplings);
             match self.perform_integration_step() {
                 IntegrationStepResult::Continue => {}
-                IntegrationStepResult::Stability(result) => {
+                IntegrationStepResult::Divergence(divergence) => {
+                    return IntegrationResult::Diverged(self.time_step.log_scale - 0.25, divergence);
+                }
+                IntegrationStepResult::Stability(result, certified_violation) => {
                     return if i == 0 {
                         IntegrationResult::InitiallyUnstable
                     } else {
                         if let FinalStabilityResult::UnstableAllowed(StabilityResult::ViolatedReqInit) = result {
                             return IntegrationResult::Invalid;
                         }
-                        IntegrationResult::Broken(self.time_step.log_scale - 0.25, result)
+                        let mut violations = self.model.violated_stability_conditions(&self.time_step.couplings);
+                        // The f64 evaluation can disagree with a certified decision close to the
+                        // boundary, whose violation comes first like the one of stability_condition
+                        if let Some(violation) = certified_violation {
+                            violations.retain(|v| v.index != violation.index);
+                            violations.insert(0, violation);
+                        }
+                        let gildener_weinberg = violations
+                            .first()
+                            .filter(|_| self.params.gildener_weinberg)
+                            .and_then(|violation| self.model.gildener_weinberg(&self.time_step.couplings, violation));
+                        IntegrationResult::Broken {
+                            log_scale: self.time_step.log_scale - 0.25,
+                            result,
+                            violations,
+                            gildener_weinberg,
+                            end_point: self.end_point(i + 1, true),
+                        }
                     };
                 }
-                IntegrationStepResult::Perturbativity => {
-                    return IntegrationResult::PerturbativityViolated(self.time_step.log_scale - 0.25);
+                IntegrationStepResult::Perturbativity(violation) => {
+                    return IntegrationResult::PerturbativityViolated(
+                        self.time_step.log_scale - 0.25,
+                        violation,
+                        self.end_point(i + 1, false),
+                    );
                 }
             }
         }
-        IntegrationResult::Unbroken
+        IntegrationResult::Unbroken(self.end_point(self.params.num_steps, false))
     }
     
-    pub fn reset(&mut self, initial_couplings: &Couplings<N>) {
+    // Runs the couplings to log_scale with at most the step size of the parameters, ignoring every
+    // event apart from divergences
+    pub fn run_to(&mut self, log_scale: f64) -> Result<(), Divergence> {
+        let distance = log_scale - self.time_step.log_scale;
+        let num_steps = (distance / self.step_size()).abs().ceil() as usize;
+        for _ in 0..num_steps {
+            let derivative = self.derivative(&self.time_step.couplings.couplings);
+            if let Some(divergence) = self.advance(derivative, distance / num_steps as f64) {
+                return Err(divergence);
+            }
+        }
+        Ok(())
+    }
+
+    // The current couplings followed by those at num_samples evenly spaced scales up to log_scale
+    pub fn trajectory(&mut self, log_scale: f64, num_samples: usize) -> Result<Vec<TimeStep<N, S>>, Divergence> {
+        let start = self.time_step.log_scale;
+        let mut trajectory = vec![self.time_step.clone()];
+        for k in 1..=num_samples {
+            self.run_to(start + (log_scale - start) * k as f64 / num_samples as f64)?;
+            trajectory.push(self.time_step.clone());
+        }
+        Ok(trajectory)
+    }
+
+    // Integrates the initial couplings at every loop order, keeping params.loop_order
+    pub fn compare_loop_orders(&mut self, initial_couplings: &Couplings<N, S>) -> LoopOrderComparison {
+        let loop_orders = self.model.loop_orders();
+        let highest = loop_orders.iter().copied().max().unwrap_or(LoopOrder::One);
+        let loop_order = self.params.loop_order;
+        let runs = LoopOrder::ALL
+            .into_iter()
+            .filter(|&order| order <= highest)
+            .map(|order| {
+                self.params.loop_order = order;
+                self.reset(initial_couplings);
+                let result = self.perform_full_integration();
+                LoopOrderRun {
+                    loop_order: order,
+                    complete: loop_orders.iter().all(|&available| available >= order),
+                    outcome: result.outcome(),
+                    event_scale: result.event_scale(),
+                }
+            })
+            .collect();
+        self.params.loop_order = loop_order;
+        self.reset(initial_couplings);
+        LoopOrderComparison { runs }
+    }
+
+    pub fn reset(&mut self, initial_couplings: &Couplings<N, S>) {
         self.time_step.couplings.couplings = initial_couplings.couplings;
         self.time_step.log_scale = self.params.initial_scale;
+        self.previous_step = None;
+        self.tangent = self.params.sensitivity.then(identity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::gauge_theory::GaugeTheory;
+    use crate::models::main_model::MainModel;
+    use crate::test_support::{stub_params, test_params};
+    use crate::util::constants::PI_4_2;
+
+    #[test]
+    fn test_compare_loop_orders() {
+        let params = IntegrationParameters { loop_order: LoopOrder::Two, ..test_params() };
+        let couplings = Couplings {
+            couplings: [0.425, 0.3, -0.3, 0.1, 0.0, 0.1, -0.05],
+        };
+        let mut integrator = Integrator::new(params, Box::new(MainModel), couplings.clone());
+        let comparison = integrator.compare_loop_orders(&couplings);
+
+        let complete: Vec<_> = comparison.runs.iter().map(|run| (run.loop_order, run.complete)).collect();
+        assert_eq!(complete, [(LoopOrder::One, true), (LoopOrder::Two, true), (LoopOrder::Three, false)]);
+        assert_eq!(integrator.params.loop_order, LoopOrder::Two);
+
+        // The comparison agrees with a plain integration at the same loop order
+        let result = integrator.perform_full_integration();
+        assert_eq!(comparison.runs[1].outcome, result.outcome());
+        assert_eq!(comparison.runs[1].event_scale, result.event_scale());
+        assert!(comparison.breaking_scale_spread().unwrap() > 0.);
+    }
+
+    #[test]
+    fn test_implicit_methods_near_landau_pole() {
+        // One-loop running towards the Landau pole at t = 16 pi^2 / (2 b g0^2) ~ 8.77
+        let (b, g0, t) = (4., 1.5, 8.);
+        let expected = (g0.powi(-2) - 2. * b * t / PI_4_2).powf(-0.5);
+        let error = |method| {
+            let params = IntegrationParameters {
+                initial_scale: 0.,
+                final_scale: t,
+                num_steps: 200,
+                method,
+                ..stub_params()
+            };
+            let model = GaugeTheory { coefficients: [b] };
+            let mut integrator = Integrator::new(params, Box::new(model), Couplings { couplings: [g0] });
+            integrator.run_to(t).unwrap();
+            (integrator.time_step.couplings.couplings[0] - expected).abs()
+        };
+        let euler = error(IntegrationMethod::Euler);
+        for method in [IntegrationMethod::Rosenbrock, IntegrationMethod::ImplicitMidpoint, IntegrationMethod::Bdf2] {
+            assert!(error(method) < euler / 10.);
+        }
     }
 }
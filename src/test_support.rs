@@ -0,0 +1,78 @@
+use crate::model::{BetaFunctionValue, BreakingPattern, Couplings, LoopOrder, Model};
+use crate::simulation::IntegrationParameters;
+use crate::util::constants::PI_4_2;
+use crate::util::stability::{FinalStabilityResult, StabilityCondition, StabilityResult, StabilityViolation};
+
+// Model with the given beta functions and stability condition, without enumerated stability
+// conditions or breaking patterns
+#[derive(Clone, Copy)]
+pub struct StubModel<const N: usize> {
+    pub beta_function: fn(&Couplings<N>) -> [BetaFunctionValue; N],
+    pub stability_condition: fn(&Couplings<N>) -> FinalStabilityResult,
+}
+impl<const N: usize> Model<N> for StubModel<N> {
+    fn beta_function(&self, couplings: &Couplings<N>) -> [BetaFunctionValue; N] {
+        (self.beta_function)(couplings)
+    }
+    fn stability_condition(&self, couplings: &Couplings<N>) -> FinalStabilityResult {
+        (self.stability_condition)(couplings)
+    }
+    fn stability_conditions(&self) -> &'static [StabilityCondition] {
+        &[]
+    }
+    fn violated_stability_conditions(&self, _couplings: &Couplings<N>) -> Vec<StabilityViolation> {
+        Vec::new()
+    }
+    fn breaking_patterns(&self) -> &'static [BreakingPattern] {
+        &[]
+    }
+    fn classify_vacuum(&self, _violation: &StabilityViolation) -> Option<BreakingPattern> {
+        None
+    }
+}
+
+pub fn no_running<const N: usize>(_couplings: &Couplings<N>) -> [BetaFunctionValue; N] {
+    std::array::from_fn(|_| BetaFunctionValue { b1: 0., b2: 0., b3: 0. })
+}
+
+pub fn stable<const N: usize>(_couplings: &Couplings<N>) -> FinalStabilityResult {
+    FinalStabilityResult::Stable
+}
+
+pub fn allowed_violation() -> FinalStabilityResult {
+    FinalStabilityResult::UnstableAllowed(StabilityResult::Violated1([1., 0., 0.]))
+}
+
+// beta_1 = lambda_2, beta_2 = 0, unstable for lambda_1 <= 0, which happens at
+// t_break = t0 - lambda_1(t0) / lambda_2(t0)
+pub const LINEAR_RUNNING: StubModel<2> = StubModel {
+    beta_function: |couplings| {
+        [
+            BetaFunctionValue { b1: PI_4_2 * couplings.couplings[1], b2: 0., b3: 0. },
+            BetaFunctionValue { b1: 0., b2: 0., b3: 0. },
+        ]
+    },
+    stability_condition: |couplings| {
+        if couplings.couplings[0] > 0. { FinalStabilityResult::Stable } else { allowed_violation() }
+    },
+};
+
+// The default integration with a tenth of the steps
+pub fn test_params() -> IntegrationParameters {
+    IntegrationParameters {
+        num_steps: 100000,
+        ..Default::default()
+    }
+}
+
+// One-loop integration of a StubModel from t = 10 to 0
+pub fn stub_params() -> IntegrationParameters {
+    IntegrationParameters {
+        initial_scale: 10.,
+        final_scale: 0.,
+        num_steps: 1000,
+        max_relative_change: 1.,
+        loop_order: LoopOrder::One,
+        ..test_params()
+    }
+}
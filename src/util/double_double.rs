@@ -0,0 +1,165 @@
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+// Unevaluated sum hi + lo with |lo| <= ulp(hi) / 2, giving roughly 106 bits of mantissa
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DoubleDouble {
+    pub hi: f64,
+    pub lo: f64,
+}
+
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    let e = (a - (s - bb)) + (b - bb);
+    (s, e)
+}
+
+fn quick_two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let e = b - (s - a);
+    (s, e)
+}
+
+fn two_prod(a: f64, b: f64) -> (f64, f64) {
+    let p = a * b;
+    let e = a.mul_add(b, -p);
+    (p, e)
+}
+
+impl DoubleDouble {
+    pub const PI: Self = Self {
+        hi: std::f64::consts::PI,
+        lo: 1.2246467991473532e-16,
+    };
+
+    pub const fn from_f64(x: f64) -> Self {
+        Self { hi: x, lo: 0. }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        self.hi + self.lo
+    }
+
+    fn renormalize(hi: f64, lo: f64) -> Self {
+        let (hi, lo) = quick_two_sum(hi, lo);
+        Self { hi, lo }
+    }
+
+    pub fn abs(self) -> Self {
+        if self.hi < 0. { -self } else { self }
+    }
+
+    pub fn sqrt(self) -> Self {
+        if self.hi <= 0. {
+            return Self::from_f64(self.hi.sqrt());
+        }
+        // One Newton step on top of the f64 square root doubles the number of correct digits
+        let x = Self::from_f64(self.hi.sqrt());
+        x + (self - x * x) / (Self::from_f64(2.) * x)
+    }
+
+    pub fn powi(self, n: i32) -> Self {
+        let mut result = Self::from_f64(1.);
+        let mut base = self;
+        let mut exponent = n.unsigned_abs();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exponent >>= 1;
+        }
+        if n < 0 { Self::from_f64(1.) / result } else { result }
+    }
+
+    pub fn is_nan(self) -> bool {
+        self.hi.is_nan() || self.lo.is_nan()
+    }
+}
+
+impl From<f64> for DoubleDouble {
+    fn from(x: f64) -> Self {
+        Self::from_f64(x)
+    }
+}
+
+impl PartialOrd for DoubleDouble {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.hi.partial_cmp(&other.hi)? {
+            Ordering::Equal => self.lo.partial_cmp(&other.lo),
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl Neg for DoubleDouble {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { hi: -self.hi, lo: -self.lo }
+    }
+}
+
+impl Add for DoubleDouble {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (s, e) = two_sum(self.hi, other.hi);
+        let (t, f) = two_sum(self.lo, other.lo);
+        let (s, e) = quick_two_sum(s, e + t);
+        Self::renormalize(s, e + f)
+    }
+}
+
+impl AddAssign for DoubleDouble {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for DoubleDouble {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Mul for DoubleDouble {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let (p, e) = two_prod(self.hi, other.hi);
+        Self::renormalize(p, e + (self.hi * other.lo + self.lo * other.hi))
+    }
+}
+
+impl Div for DoubleDouble {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let q1 = self.hi / other.hi;
+        let r = self - other * Self::from_f64(q1);
+        let q2 = r.hi / other.hi;
+        let r = r - other * Self::from_f64(q2);
+        let q3 = r.hi / other.hi;
+        Self::renormalize(q1, q2) + Self::from_f64(q3)
+    }
+}
+
+impl Mul<DoubleDouble> for f64 {
+    type Output = DoubleDouble;
+
+    fn mul(self, other: DoubleDouble) -> DoubleDouble {
+        DoubleDouble::from_f64(self) * other
+    }
+}
+
+impl Div<f64> for DoubleDouble {
+    type Output = Self;
+
+    fn div(self, other: f64) -> Self {
+        self / DoubleDouble::from_f64(other)
+    }
+}
@@ -0,0 +1,179 @@
+use crate::model::{BetaFunctionValue, Couplings, Jacobian, Model};
+use crate::util::double_double::DoubleDouble;
+use crate::util::scalar::Scalar;
+use std::cmp::Ordering;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+// Value together with its gradient with respect to N variables. Evaluating a function on dual
+// numbers gives its exact derivatives (forward-mode automatic differentiation).
+#[derive(Debug, Clone, Copy)]
+pub struct Dual<const N: usize, S: Scalar = f64> {
+    pub value: S,
+    pub gradient: [S; N],
+}
+impl<const N: usize, S: Scalar> Dual<N, S> {
+    pub fn constant(value: S) -> Self {
+        Self {
+            value,
+            gradient: [S::from_f64(0.); N],
+        }
+    }
+
+    // The i-th of the N variables
+    pub fn variable(value: S, i: usize) -> Self {
+        let mut gradient = [S::from_f64(0.); N];
+        gradient[i] = S::from_f64(1.);
+        Self { value, gradient }
+    }
+
+    // Chain rule for f(self) with f'(self.value) = derivative
+    fn chain(self, value: S, derivative: S) -> Self {
+        Self {
+            value,
+            gradient: self.gradient.map(|g| g * derivative),
+        }
+    }
+}
+
+impl<const N: usize, S: Scalar> PartialEq for Dual<N, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+impl<const N: usize, S: Scalar> PartialOrd for Dual<N, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<const N: usize, S: Scalar> Add for Dual<N, S> {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            value: self.value + other.value,
+            gradient: std::array::from_fn(|i| self.gradient[i] + other.gradient[i]),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> AddAssign for Dual<N, S> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl<const N: usize, S: Scalar> Sub for Dual<N, S> {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            value: self.value - other.value,
+            gradient: std::array::from_fn(|i| self.gradient[i] - other.gradient[i]),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> Mul for Dual<N, S> {
+    type Output = Self;
+    // Product rule
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, other: Self) -> Self {
+        Self {
+            value: self.value * other.value,
+            gradient: std::array::from_fn(|i| self.gradient[i] * other.value + self.value * other.gradient[i]),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> Div for Dual<N, S> {
+    type Output = Self;
+    // Quotient rule
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: Self) -> Self {
+        let value = self.value / other.value;
+        Self {
+            value,
+            gradient: std::array::from_fn(|i| (self.gradient[i] - value * other.gradient[i]) / other.value),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> Neg for Dual<N, S> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            value: -self.value,
+            gradient: self.gradient.map(|g| -g),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> Div<f64> for Dual<N, S> {
+    type Output = Self;
+    fn div(self, other: f64) -> Self {
+        Self {
+            value: self.value / other,
+            gradient: self.gradient.map(|g| g / other),
+        }
+    }
+}
+impl<const N: usize, S: Scalar> Mul<Dual<N, S>> for f64 {
+    type Output = Dual<N, S>;
+    fn mul(self, other: Dual<N, S>) -> Dual<N, S> {
+        let factor = S::from_f64(self);
+        Dual {
+            value: factor * other.value,
+            gradient: other.gradient.map(|g| factor * g),
+        }
+    }
+}
+
+impl<const N: usize, S: Scalar> Scalar for Dual<N, S> {
+    const EPSILON: f64 = S::EPSILON;
+
+    fn from_f64(x: f64) -> Self {
+        Self::constant(S::from_f64(x))
+    }
+    fn to_f64(self) -> f64 {
+        self.value.to_f64()
+    }
+    fn to_double_double(self) -> DoubleDouble {
+        self.value.to_double_double()
+    }
+
+    fn loop_factor() -> Self {
+        Self::constant(S::loop_factor())
+    }
+
+    fn abs(self) -> Self {
+        if self.value < S::from_f64(0.) { -self } else { self }
+    }
+    fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+        self.chain(value, S::from_f64(0.5) / value)
+    }
+    fn powi(self, n: i32) -> Self {
+        if n == 0 {
+            return Self::from_f64(1.);
+        }
+        self.chain(self.value.powi(n), S::from_f64(n as f64) * self.value.powi(n - 1))
+    }
+    fn is_nan(self) -> bool {
+        self.value.is_nan() || self.gradient.iter().any(|g| g.is_nan())
+    }
+    fn is_finite(self) -> bool {
+        self.value.is_finite() && self.gradient.iter().all(|g| g.is_finite())
+    }
+}
+
+// Exact Jacobian of beta functions that are written generically over the scalar type, as all the
+// polynomial ones are
+pub fn exact_jacobian<const N: usize, S: Scalar, M: Model<N, Dual<N, S>> + ?Sized>(
+    model: &M,
+    couplings: &Couplings<N, S>,
+) -> Jacobian<N, S> {
+    let variables = Couplings {
+        couplings: std::array::from_fn(|j| Dual::variable(couplings.couplings[j], j)),
+    };
+    let beta_functions = model.beta_function(&variables);
+    std::array::from_fn(|i| {
+        std::array::from_fn(|j| BetaFunctionValue {
+            b1: beta_functions[i].b1.gradient[j],
+            b2: beta_functions[i].b2.gradient[j],
+            b3: beta_functions[i].b3.gradient[j],
+        })
+    })
+}
@@ -0,0 +1,124 @@
+use crate::util::constants::PI_4_2;
+use crate::util::linear_algebra::{symmetric_eigen, Matrix};
+
+// Tangent squared masses above minus this fraction of the largest one count as flat. The flat
+// direction is only known to the accuracy of the step in which the breaking was detected, which
+// leaves pseudo-Goldstone bosons with tiny masses of either sign.
+const TACHYON_TOLERANCE: f64 = 1E-4;
+
+// Quartic polynomial sum_k c_k q_i(phi) q_j(phi) in the quadratic forms q_i(phi) = phi^T Q_i phi,
+// with the forms Q_i symmetric
+pub struct QuarticPotential<'a> {
+    pub forms: &'a [Matrix],
+    // (c_k, i, j)
+    pub terms: Vec<(f64, usize, usize)>,
+}
+impl QuarticPotential<'_> {
+    fn evaluate_forms(&self, phi: &[f64]) -> (Vec<f64>, Vec<Vec<f64>>) {
+        let products: Vec<Vec<f64>> = self.forms.iter().map(|form| form.mul_vec(phi)).collect();
+        let values = products.iter().map(|p| p.iter().zip(phi).map(|(a, b)| a * b).sum()).collect();
+        (values, products)
+    }
+
+    pub fn value(&self, phi: &[f64]) -> f64 {
+        let (values, _) = self.evaluate_forms(phi);
+        self.terms.iter().map(|&(c, i, j)| c * values[i] * values[j]).sum()
+    }
+
+    // d^2 (q_i q_j) = 2 Q_i q_j + 2 Q_j q_i + 4 (Q_i phi)(Q_j phi)^T + 4 (Q_j phi)(Q_i phi)^T
+    pub fn hessian(&self, phi: &[f64]) -> Matrix {
+        let n = phi.len();
+        let (values, products) = self.evaluate_forms(phi);
+        let mut result = Matrix::zeros(n, n);
+        for &(c, i, j) in &self.terms {
+            result.add_scaled(2. * c * values[j], &self.forms[i]);
+            result.add_scaled(2. * c * values[i], &self.forms[j]);
+            for (k, a) in products[i].iter().enumerate() {
+                for (l, b) in products[j].iter().enumerate() {
+                    let outer = 4. * c * a * b;
+                    result[(k, l)] += outer;
+                    result[(l, k)] += outer;
+                }
+            }
+        }
+        result
+    }
+}
+
+// Field-dependent squared mass matrices for a given vev
+pub struct MassMatrices {
+    pub vector: Matrix,
+    pub scalar: Matrix,
+    // M^dagger M of the Weyl fermions
+    pub fermion: Matrix,
+}
+
+// One-loop Gildener-Weinberg analysis along a flat direction of the tree-level potential, where
+// V_1 = A phi^4 + B phi^4 ln(phi^2 / mu^2). All masses are in units of the vev.
+#[derive(Debug, Clone)]
+pub struct GildenerWeinberg {
+    pub b: f64,
+    // sqrt(8 B), the mass of the pseudo-Goldstone boson of scale invariance
+    pub scalon_mass: f64,
+    // Ascending, the massless states included
+    pub vector_masses_squared: Vec<f64>,
+    pub fermion_masses_squared: Vec<f64>,
+    // Scalars orthogonal to the flat direction; negative values mean that the flat direction is not
+    // a minimum of the tree-level potential
+    pub scalar_masses_squared: Vec<f64>,
+}
+impl GildenerWeinberg {
+    // masses evaluated at the unit vector direction along the flat direction
+    pub fn new(masses: &MassMatrices, direction: &[f64]) -> Self {
+        let eigenvalues = |matrix: &Matrix| symmetric_eigen(matrix).into_iter().map(|(value, _)| value).collect::<Vec<_>>();
+
+        // The tree-level mass of the scalon itself vanishes, so the scalar mass matrix is restricted
+        // to the directions orthogonal to it. Once the direction is no longer exactly flat,
+        // V(n) = n^T M n / 12 < 0, the shift by the Lagrange multiplier 4 V(n) of the unit sphere
+        // keeps the would-be Goldstone bosons massless.
+        let n = direction.len();
+        let radial: f64 = masses.scalar.mul_vec(direction).iter().zip(direction).map(|(a, b)| a * b).sum();
+        let mut shifted = masses.scalar.clone();
+        for i in 0..n {
+            shifted[(i, i)] -= radial / 3.;
+        }
+        let mut projector = Matrix::zeros(n, n);
+        for i in 0..n {
+            for j in 0..n {
+                projector[(i, j)] = if i == j { 1. } else { 0. } - direction[i] * direction[j];
+            }
+        }
+        let tangent = projector.mul(&shifted).mul(&projector);
+        let mut scalar_masses_squared = eigenvalues(&tangent);
+        // Drop the (zero) eigenvalue of the flat direction
+        if let Some(flat) = scalar_masses_squared
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.abs().total_cmp(&b.abs()))
+            .map(|(i, _)| i)
+        {
+            scalar_masses_squared.remove(flat);
+        }
+
+        let vector_masses_squared = eigenvalues(&masses.vector);
+        let fermion_masses_squared = eigenvalues(&masses.fermion);
+
+        let fourth = |values: &[f64]| values.iter().map(|m2| m2 * m2).sum::<f64>();
+        let b = (3. * fourth(&vector_masses_squared) + fourth(&scalar_masses_squared)
+            - 2. * fourth(&fermion_masses_squared))
+            / (4. * PI_4_2);
+
+        Self {
+            b,
+            scalon_mass: (8. * b).max(0.).sqrt(),
+            vector_masses_squared,
+            fermion_masses_squared,
+            scalar_masses_squared,
+        }
+    }
+
+    pub fn is_minimum(&self) -> bool {
+        let largest = self.scalar_masses_squared.iter().fold(0_f64, |a, b| a.max(b.abs()));
+        self.b > 0. && self.scalar_masses_squared.iter().all(|&m2| m2 >= -TACHYON_TOLERANCE * largest)
+    }
+}
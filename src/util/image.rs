@@ -9,11 +9,12 @@ pub struct Layer<T, const NX: usize, const NY: usize> {
     merge_behaviour: fn(&T, T) -> T,
 }
 impl<T : Copy, const NX: usize, const NY: usize> Layer<T, NX, NY> {
+    // Values outside of the ranges are dropped. Values on the edges, or just outside from rounding, go
+    // to the edge pixels.
     pub fn write(&mut self, x: f64, y: f64, data: T) {
-        let x_index =
-            ((x - self.range_x.0) / (self.range_x.1 - self.range_x.0) * NX as f64) as usize;
-        let y_index =
-            ((y - self.range_y.0) / (self.range_y.1 - self.range_y.0) * NY as f64) as usize;
+        let (Some(x_index), Some(y_index)) = (pixel::<NX>(x, self.range_x), pixel::<NY>(y, self.range_y)) else {
+            return;
+        };
         self.data[x_index][y_index] = (self.merge_behaviour)(&self.data[x_index][y_index], data);
     }
     
@@ -26,6 +27,17 @@ impl<T : Copy, const NX: usize, const NY: usize> Layer<T, NX, NY> {
     }
 }
 
+// Fraction of the range by which values may lie outside of it and still be drawn
+const EDGE_TOLERANCE: f64 = 1E-9;
+
+fn pixel<const N: usize>(value: f64, (min, max): (f64, f64)) -> Option<usize> {
+    let position = (value - min) / (max - min);
+    if !(-EDGE_TOLERANCE..=1. + EDGE_TOLERANCE).contains(&position) {
+        return None;
+    }
+    Some(((position * N as f64) as usize).min(N - 1))
+}
+
 pub fn boolean_layer<const NX: usize, const NY: usize>(
     range_x: (f64, f64),
     range_y: (f64, f64),
@@ -142,6 +154,22 @@ impl<const NX: usize, const NY: usize> Image<NX, NY> {
         }
     }
 
+    pub fn draw_dominant_layers(&mut self, layers: &[Layer<i64, NX, NY>], colors: &[u32]) {
+        // Color every pixel by the layer with the highest count
+        for i in 0..NX {
+            for j in 0..NY {
+                let dominant = layers
+                    .iter()
+                    .zip(colors)
+                    .filter(|(layer, _)| layer.data[i][j] > 0)
+                    .max_by_key(|(layer, _)| layer.data[i][j]);
+                if let Some((_, &color)) = dominant {
+                    self.data[i][j] = color;
+                }
+            }
+        }
+    }
+
     pub fn draw_boolean_layer(&mut self, layer: &Layer<bool, NX, NY>, color: u32) {
         for i in 0..NX {
             for j in 0..NY {
@@ -240,3 +268,20 @@ impl<const NX: usize, const NY: usize> Image<NX, NY> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_outside_of_the_ranges_are_dropped() {
+        let mut layer: Layer<i64, 4, 4> = count_layer((0., 1.), (-1., 1.));
+        layer.write(1., 1., 1);
+        layer.write(1. + 1E-12, -1. - 1E-12, 1);
+        layer.write(1.1, 0., 1);
+        layer.write(0.5, -1.5, 1);
+        layer.write(f64::NAN, 0., 1);
+        assert_eq!((layer.data[3][3], layer.data[3][0]), (1, 1));
+        assert_eq!(layer.data.iter().flatten().sum::<i64>(), 2);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::util::double_double::DoubleDouble;
+use crate::util::scalar::Scalar;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Double,
+    DoubleDouble,
+}
+
+// A scalar type that can bound the exact result of its own arithmetic from below and above
+pub trait IntervalBound: Scalar {
+    // Maximum number of bisections of a unit interval that still resolve distinct points
+    const BISECTION_DEPTH: u32;
+
+    // Widen the result of a single arithmetic operation so it encloses the exact value
+    fn down(self) -> Self;
+    fn up(self) -> Self;
+
+    // Tightest bounds enclosing an exactly known value
+    fn enclosing(x: DoubleDouble) -> (Self, Self);
+}
+
+impl IntervalBound for f64 {
+    const BISECTION_DEPTH: u32 = 48;
+
+    // IEEE arithmetic and sqrt are correctly rounded, so one ulp in either direction suffices
+    fn down(self) -> Self {
+        self.next_down()
+    }
+    fn up(self) -> Self {
+        self.next_up()
+    }
+
+    fn enclosing(x: DoubleDouble) -> (Self, Self) {
+        if x.lo == 0. {
+            (x.hi, x.hi)
+        } else {
+            let x = x.to_f64();
+            (x.next_down(), x.next_up())
+        }
+    }
+}
+
+// Relative error bound of the double-double operations, with a generous safety margin
+const DOUBLE_DOUBLE_EPSILON: f64 = 1.0 / (1u128 << 100) as f64;
+
+impl IntervalBound for DoubleDouble {
+    const BISECTION_DEPTH: u32 = 96;
+
+    fn down(self) -> Self {
+        let error = self.hi.abs() * DOUBLE_DOUBLE_EPSILON + f64::MIN_POSITIVE;
+        self - DoubleDouble::from_f64(error)
+    }
+    fn up(self) -> Self {
+        let error = self.hi.abs() * DOUBLE_DOUBLE_EPSILON + f64::MIN_POSITIVE;
+        self + DoubleDouble::from_f64(error)
+    }
+
+    fn enclosing(x: DoubleDouble) -> (Self, Self) {
+        (x, x)
+    }
+}
+
+// Closed interval [lo, hi] enclosing an exact real value. A NaN bound means nothing is known,
+// so every predicate below is written such that it is false for NaN.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval<B: IntervalBound = f64> {
+    pub lo: B,
+    pub hi: B,
+}
+impl<B: IntervalBound> Interval<B> {
+    pub fn new(lo: B, hi: B) -> Self {
+        Self { lo, hi }
+    }
+
+    pub fn point(x: f64) -> Self {
+        let x = B::from_f64(x);
+        Self { lo: x, hi: x }
+    }
+
+    pub fn enclose<S: Scalar>(x: S) -> Self {
+        let (lo, hi) = B::enclosing(x.to_double_double());
+        Self { lo, hi }
+    }
+
+    pub fn mid(&self) -> B {
+        let half = B::from_f64(0.5);
+        self.lo * half + self.hi * half
+    }
+
+    pub fn width(&self) -> B {
+        (self.hi - self.lo).up()
+    }
+
+    pub fn bisect(&self) -> (Self, Self) {
+        let mid = self.mid();
+        (Self::new(self.lo, mid), Self::new(mid, self.hi))
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.lo > B::from_f64(0.)
+    }
+
+    pub fn is_nonpositive(&self) -> bool {
+        self.hi <= B::from_f64(0.)
+    }
+
+    pub fn square(self) -> Self {
+        let zero = B::from_f64(0.);
+        let lo = self.lo * self.lo;
+        let hi = self.hi * self.hi;
+        if self.lo >= zero {
+            Self::new(lo.down(), hi.up())
+        } else if self.hi <= zero {
+            Self::new(hi.down(), lo.up())
+        } else {
+            Self::new(zero, max(lo, hi).up())
+        }
+    }
+
+    pub fn sqrt(self) -> Self {
+        let zero = B::from_f64(0.);
+        let lo = if self.lo > zero { self.lo.sqrt().down() } else { zero };
+        let hi = if self.hi >= zero { self.hi.sqrt().up() } else { B::from_f64(f64::NAN) };
+        Self::new(lo, hi)
+    }
+}
+
+impl<B: IntervalBound> Neg for Interval<B> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.hi, -self.lo)
+    }
+}
+
+impl<B: IntervalBound> Add for Interval<B> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new((self.lo + other.lo).down(), (self.hi + other.hi).up())
+    }
+}
+
+impl<B: IntervalBound> Sub for Interval<B> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new((self.lo - other.hi).down(), (self.hi - other.lo).up())
+    }
+}
+
+fn min<B: IntervalBound>(a: B, b: B) -> B {
+    // Propagate NaN instead of silently dropping it
+    if a < b || b.is_nan() { a } else { b }
+}
+
+fn max<B: IntervalBound>(a: B, b: B) -> B {
+    if a > b || b.is_nan() { a } else { b }
+}
+
+impl<B: IntervalBound> Mul for Interval<B> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let products = [
+            self.lo * other.lo,
+            self.lo * other.hi,
+            self.hi * other.lo,
+            self.hi * other.hi,
+        ];
+        let mut lo = products[0];
+        let mut hi = products[0];
+        for &product in &products[1..] {
+            lo = min(lo, product);
+            hi = max(hi, product);
+        }
+        Self::new(lo.down(), hi.up())
+    }
+}
+
+impl<B: IntervalBound> Div for Interval<B> {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let zero = B::from_f64(0.);
+        if !(other.lo > zero || other.hi < zero) {
+            let nan = B::from_f64(f64::NAN);
+            return Self::new(nan, nan);
+        }
+        let quotients = [
+            self.lo / other.lo,
+            self.lo / other.hi,
+            self.hi / other.lo,
+            self.hi / other.hi,
+        ];
+        let mut lo = quotients[0];
+        let mut hi = quotients[0];
+        for &quotient in &quotients[1..] {
+            lo = min(lo, quotient);
+            hi = max(hi, quotient);
+        }
+        Self::new(lo.down(), hi.up())
+    }
+}
+
+impl<B: IntervalBound> Mul<Interval<B>> for f64 {
+    type Output = Interval<B>;
+
+    fn mul(self, other: Interval<B>) -> Interval<B> {
+        Interval::point(self) * other
+    }
+}
+
+impl<B: IntervalBound> Div<f64> for Interval<B> {
+    type Output = Self;
+
+    fn div(self, other: f64) -> Self {
+        self / Interval::point(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enclosure() {
+        let third = Interval::<f64>::point(1.) / Interval::point(3.);
+        let one = 3. * third;
+        assert!(one.lo <= 1. && one.hi >= 1.);
+        assert!(!(one - Interval::point(1.)).is_positive());
+        assert!(!(one - Interval::point(1.)).is_nonpositive());
+    }
+
+    #[test]
+    fn test_double_double_is_tighter() {
+        let x = Interval::<f64>::point(0.1) * Interval::point(0.3) - Interval::point(0.03);
+        let y = Interval::<DoubleDouble>::point(0.1) * Interval::point(0.3) - Interval::point(0.03);
+        assert!(y.width().to_f64() < x.width() * 1e-10);
+    }
+}
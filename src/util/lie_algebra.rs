@@ -0,0 +1,155 @@
+use crate::util::linear_algebra::{symmetric_eigen, Matrix};
+
+// Squared masses below this fraction of the largest one are considered to vanish. Eigenvalues are
+// only accurate to ~1e-16 of the largest one, so this corresponds to vevs aligned to ~1e-7. This is
+// looser than the absolute 1e-12 on the vev components of the vev-based classification it replaced,
+// which cannot be resolved through the squared masses.
+const ZERO_TOLERANCE: f64 = 1E-14;
+
+// Gram matrix of a set of vectors
+fn gram(vectors: &[Vec<f64>]) -> Matrix {
+    let n = vectors.len();
+    let mut result = Matrix::zeros(n, n);
+    for i in 0..n {
+        for j in 0..=i {
+            let dot = vectors[i].iter().zip(&vectors[j]).map(|(a, b)| a * b).sum();
+            result[(i, j)] = dot;
+            result[(j, i)] = dot;
+        }
+    }
+    result
+}
+
+fn is_zero(eigenvalue: f64, largest: f64) -> bool {
+    eigenvalue <= ZERO_TOLERANCE * largest
+}
+
+// Real representation of the gauge algebra on the real components of a scalar field. Generators
+// are real antisymmetric matrices; complex representations have to be written in terms of their
+// real and imaginary parts.
+#[derive(Debug, Clone)]
+pub struct Representation {
+    pub name: &'static str,
+    pub generators: Vec<Matrix>,
+}
+impl Representation {
+    pub fn dimension(&self) -> usize {
+        self.generators.first().map_or(0, |generator| generator.rows)
+    }
+}
+
+// Gauge algebra together with the representations of the scalar fields of a model. Taken together
+// the representations have to be faithful, which is always the case if the adjoint is among them.
+#[derive(Debug, Clone)]
+pub struct GaugeAlgebra {
+    pub name: &'static str,
+    pub representations: Vec<Representation>,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnbrokenSubalgebra {
+    pub dimension: usize,
+    pub rank: usize,
+    // Basis of the unbroken subalgebra as coefficients of the generators of the full algebra
+    pub generators: Vec<Vec<f64>>,
+    // Masses of all gauge bosons in ascending order, including the massless ones
+    pub gauge_boson_masses: Vec<f64>,
+}
+
+impl GaugeAlgebra {
+    pub fn dimension(&self) -> usize {
+        self.representations[0].generators.len()
+    }
+
+    pub fn rank(&self) -> usize {
+        let vevs: Vec<_> = self.representations.iter().map(|r| vec![0.; r.dimension()]).collect();
+        self.unbroken_subalgebra(&vevs, 1.).rank
+    }
+
+    // Generator with the given coefficients, in every representation
+    fn combination(&self, coefficients: &[f64]) -> Vec<Matrix> {
+        self.representations
+            .iter()
+            .map(|representation| {
+                let n = representation.dimension();
+                let mut result = Matrix::zeros(n, n);
+                for (c, generator) in coefficients.iter().zip(&representation.generators) {
+                    result.add_scaled(*c, generator);
+                }
+                result
+            })
+            .collect()
+    }
+
+    // vevs[i] are the real components of the vev of representations[i]. The gauge boson mass matrix
+    // is M^2_ab = g^2 (T_a v) . (T_b v) for canonically normalised real field components.
+    pub fn vector_mass_matrix(&self, vevs: &[Vec<f64>], gauge_coupling: f64) -> Matrix {
+        assert_eq!(vevs.len(), self.representations.len(), "Expected one vev per representation");
+
+        let images: Vec<Vec<f64>> = (0..self.dimension())
+            .map(|a| {
+                self.representations
+                    .iter()
+                    .zip(vevs)
+                    .flat_map(|(representation, vev)| representation.generators[a].mul_vec(vev))
+                    .map(|x| gauge_coupling * x)
+                    .collect()
+            })
+            .collect();
+
+        gram(&images)
+    }
+
+    pub fn unbroken_subalgebra(&self, vevs: &[Vec<f64>], gauge_coupling: f64) -> UnbrokenSubalgebra {
+        let eigen = symmetric_eigen(&self.vector_mass_matrix(vevs, 1.));
+        let largest = eigen.last().map_or(0., |(value, _)| *value);
+        let generators: Vec<Vec<f64>> = eigen
+            .iter()
+            .filter(|(value, _)| is_zero(*value, largest))
+            .map(|(_, vector)| vector.clone())
+            .collect();
+        let gauge_boson_masses = eigen
+            .iter()
+            .map(|(value, _)| if is_zero(*value, largest) { 0. } else { gauge_coupling.abs() * value.sqrt() })
+            .collect();
+
+        UnbrokenSubalgebra {
+            dimension: generators.len(),
+            rank: self.subalgebra_rank(&generators),
+            generators,
+            gauge_boson_masses,
+        }
+    }
+
+    // The rank of a reductive Lie algebra is the dimension of the centraliser of a generic element
+    fn subalgebra_rank(&self, generators: &[Vec<f64>]) -> usize {
+        if generators.is_empty() {
+            return 0;
+        }
+
+        let mut generic = vec![0.; self.dimension()];
+        for (i, generator) in generators.iter().enumerate() {
+            // Incommensurate weights so that the element does not lie on a special subspace
+            let weight = ((i + 1) as f64 * 0.618_033_988_749_895).fract() + 0.5;
+            for (g, x) in generic.iter_mut().zip(generator) {
+                *g += weight * x;
+            }
+        }
+        let generic = self.combination(&generic);
+
+        let commutators: Vec<Vec<f64>> = generators
+            .iter()
+            .map(|generator| {
+                self.combination(generator)
+                    .iter()
+                    .zip(&generic)
+                    .flat_map(|(y, x)| x.commutator(y).as_slice().to_vec())
+                    .collect()
+            })
+            .collect();
+
+        let eigen = symmetric_eigen(&gram(&commutators));
+        let largest = eigen.last().map_or(0., |(value, _)| *value);
+        eigen.iter().filter(|(value, _)| is_zero(*value, largest)).count()
+    }
+}
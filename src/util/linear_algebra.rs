@@ -0,0 +1,430 @@
+use crate::util::scalar::Scalar;
+use std::cmp::Ordering;
+use std::ops::{Index, IndexMut};
+
+// Pivots below this fraction of the largest matrix element are considered to vanish
+const PIVOT_TOLERANCE: f64 = 1E-14;
+const JACOBI_SWEEPS: usize = 100;
+const QR_ITERATIONS: usize = 60;
+
+// Dense row-major real matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    data: Vec<f64>,
+}
+impl Matrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            data: vec![0.; rows * cols],
+        }
+    }
+
+    pub fn mul_vec(&self, v: &[f64]) -> Vec<f64> {
+        assert_eq!(self.cols, v.len(), "Dimension mismatch in matrix-vector product");
+        self.data
+            .chunks(self.cols)
+            .map(|row| row.iter().zip(v).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+
+    pub fn mul(&self, other: &Matrix) -> Matrix {
+        assert_eq!(self.cols, other.rows, "Dimension mismatch in matrix product");
+        let mut result = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                if a == 0. {
+                    continue;
+                }
+                for j in 0..other.cols {
+                    result[(i, j)] += a * other[(k, j)];
+                }
+            }
+        }
+        result
+    }
+
+    pub fn commutator(&self, other: &Matrix) -> Matrix {
+        let mut result = self.mul(other);
+        for (r, x) in result.data.iter_mut().zip(&other.mul(self).data) {
+            *r -= x;
+        }
+        result
+    }
+
+    pub fn scale(&mut self, factor: f64) {
+        for a in self.data.iter_mut() {
+            *a *= factor;
+        }
+    }
+
+    // self + factor * other
+    pub fn add_scaled(&mut self, factor: f64, other: &Matrix) {
+        for (a, b) in self.data.iter_mut().zip(&other.data) {
+            *a += factor * b;
+        }
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.data
+    }
+}
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+    fn index(&self, (i, j): (usize, usize)) -> &f64 {
+        &self.data[i * self.cols + j]
+    }
+}
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut f64 {
+        &mut self.data[i * self.cols + j]
+    }
+}
+
+// Eigenvalues in ascending order and the corresponding normalised eigenvectors of a real symmetric
+// matrix, using cyclic Jacobi rotations
+pub fn symmetric_eigen(matrix: &Matrix) -> Vec<(f64, Vec<f64>)> {
+    let n = matrix.rows;
+    let mut a = matrix.clone();
+    let mut v = Matrix::zeros(n, n);
+    for i in 0..n {
+        v[(i, i)] = 1.;
+    }
+
+    for _ in 0..JACOBI_SWEEPS {
+        let off_diagonal: f64 = (0..n).flat_map(|i| (0..i).map(move |j| (i, j))).map(|(i, j)| a[(i, j)].powi(2)).sum();
+        if off_diagonal == 0. {
+            break;
+        }
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[(p, q)] == 0. {
+                    continue;
+                }
+                let theta = (a[(q, q)] - a[(p, p)]) / (2. * a[(p, q)]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                let t = if theta == 0. { 1. } else { t };
+                let c = 1. / (t * t + 1.).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                    a[(k, p)] = c * akp - s * akq;
+                    a[(k, q)] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                    a[(p, k)] = c * apk - s * aqk;
+                    a[(q, k)] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (v[(k, p)], v[(k, q)]);
+                    v[(k, p)] = c * vkp - s * vkq;
+                    v[(k, q)] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let mut eigen: Vec<_> = (0..n).map(|i| (a[(i, i)], (0..n).map(|k| v[(k, i)]).collect())).collect();
+    eigen.sort_by(|(x, _), (y, _)| x.total_cmp(y));
+    eigen
+}
+
+// Eigenvalues (re, im) of a real square matrix, by reduction to Hessenberg form and the Francis
+// double-shift QR algorithm, in no particular order. None if the QR iteration does not converge.
+pub fn eigenvalues(matrix: &Matrix) -> Option<Vec<(f64, f64)>> {
+    let n = matrix.rows;
+    let mut a = matrix.clone();
+
+    // Hessenberg form by Gaussian elimination with pivoting
+    for m in 1..n.saturating_sub(1) {
+        let pivot = (m..n).max_by(|&i, &j| a[(i, m - 1)].abs().total_cmp(&a[(j, m - 1)].abs())).unwrap_or(m);
+        let x = a[(pivot, m - 1)];
+        if pivot != m {
+            for j in (m - 1)..n {
+                let (u, v) = (a[(pivot, j)], a[(m, j)]);
+                a[(pivot, j)] = v;
+                a[(m, j)] = u;
+            }
+            for i in 0..n {
+                let (u, v) = (a[(i, pivot)], a[(i, m)]);
+                a[(i, pivot)] = v;
+                a[(i, m)] = u;
+            }
+        }
+        if x != 0. {
+            for i in (m + 1)..n {
+                let y = a[(i, m - 1)] / x;
+                if y != 0. {
+                    a[(i, m - 1)] = 0.;
+                    for j in m..n {
+                        a[(i, j)] -= y * a[(m, j)];
+                    }
+                    for j in 0..n {
+                        a[(j, m)] += y * a[(j, i)];
+                    }
+                }
+            }
+        }
+    }
+
+    let norm: f64 = (0..n).flat_map(|i| (i.saturating_sub(1)..n).map(move |j| (i, j))).map(|(i, j)| a[(i, j)].abs()).sum();
+    let mut result = vec![(0., 0.); n];
+    // Accumulated exceptional shifts
+    let mut t = 0.;
+    let mut iterations = 0;
+    let mut nn = n;
+    while nn > 0 {
+        let last = nn - 1;
+        // Look for a single small subdiagonal element
+        let mut l = last;
+        while l > 0 {
+            let s = a[(l - 1, l - 1)].abs() + a[(l, l)].abs();
+            let s = if s == 0. { norm } else { s };
+            if a[(l, l - 1)].abs() + s == s {
+                a[(l, l - 1)] = 0.;
+                break;
+            }
+            l -= 1;
+        }
+
+        let mut x = a[(last, last)];
+        if l == last {
+            result[last] = (x + t, 0.);
+            nn -= 1;
+            iterations = 0;
+            continue;
+        }
+        let mut y = a[(last - 1, last - 1)];
+        let mut w = a[(last, last - 1)] * a[(last - 1, last)];
+        if l == last - 1 {
+            let p = 0.5 * (y - x);
+            let q = p * p + w;
+            let z = q.abs().sqrt();
+            x += t;
+            if q >= 0. {
+                let z = p + z.copysign(p);
+                result[last - 1] = (x + z, 0.);
+                result[last] = (if z != 0. { x - w / z } else { x + z }, 0.);
+            } else {
+                result[last - 1] = (x + p, -z);
+                result[last] = (x + p, z);
+            }
+            nn -= 2;
+            iterations = 0;
+            continue;
+        }
+
+        if iterations == QR_ITERATIONS {
+            return None;
+        }
+        if iterations == 10 || iterations == 20 {
+            t += x;
+            for i in 0..=last {
+                a[(i, i)] -= x;
+            }
+            let s = a[(last, last - 1)].abs() + a[(last - 1, last - 2)].abs();
+            x = 0.75 * s;
+            y = x;
+            w = -0.4375 * s * s;
+        }
+        iterations += 1;
+
+        // Form the shift and look for two consecutive small subdiagonal elements
+        let mut m = last - 2;
+        let (mut p, mut q, mut r);
+        loop {
+            let z = a[(m, m)];
+            let (u, v) = (x - z, y - z);
+            p = (u * v - w) / a[(m + 1, m)] + a[(m, m + 1)];
+            q = a[(m + 1, m + 1)] - z - u - v;
+            r = a[(m + 2, m + 1)];
+            let s = p.abs() + q.abs() + r.abs();
+            p /= s;
+            q /= s;
+            r /= s;
+            if m == l {
+                break;
+            }
+            let u = a[(m, m - 1)].abs() * (q.abs() + r.abs());
+            let v = p.abs() * (a[(m - 1, m - 1)].abs() + z.abs() + a[(m + 1, m + 1)].abs());
+            if u + v == v {
+                break;
+            }
+            m -= 1;
+        }
+        for i in (m + 2)..=last {
+            a[(i, i - 2)] = 0.;
+            if i != m + 2 {
+                a[(i, i - 3)] = 0.;
+            }
+        }
+
+        // Double QR step on rows l..=last and columns m..=last
+        for k in m..last {
+            if k != m {
+                p = a[(k, k - 1)];
+                q = a[(k + 1, k - 1)];
+                r = if k != last - 1 { a[(k + 2, k - 1)] } else { 0. };
+                x = p.abs() + q.abs() + r.abs();
+                if x != 0. {
+                    p /= x;
+                    q /= x;
+                    r /= x;
+                }
+            }
+            let s = (p * p + q * q + r * r).sqrt().copysign(p);
+            if s == 0. {
+                continue;
+            }
+            if k == m {
+                if l != m {
+                    a[(k, k - 1)] = -a[(k, k - 1)];
+                }
+            } else {
+                a[(k, k - 1)] = -s * x;
+            }
+            p += s;
+            x = p / s;
+            y = q / s;
+            let z = r / s;
+            q /= p;
+            r /= p;
+            for j in k..=last {
+                let mut p = a[(k, j)] + q * a[(k + 1, j)];
+                if k != last - 1 {
+                    p += r * a[(k + 2, j)];
+                    a[(k + 2, j)] -= p * z;
+                }
+                a[(k + 1, j)] -= p * y;
+                a[(k, j)] -= p * x;
+            }
+            for i in l..=last.min(k + 3) {
+                let mut p = x * a[(i, k)] + y * a[(i, k + 1)];
+                if k != last - 1 {
+                    p += z * a[(i, k + 2)];
+                    a[(i, k + 2)] -= p * r;
+                }
+                a[(i, k + 1)] -= p * q;
+                a[(i, k)] -= p;
+            }
+        }
+    }
+    Some(result)
+}
+
+// Solution of matrix x = rhs by Gaussian elimination with partial pivoting, failing with the column
+// without a usable pivot
+pub fn solve_linear(matrix: Matrix, mut rhs: Vec<f64>) -> Result<Vec<f64>, usize> {
+    let mut data = matrix.data;
+    let mut rows: Vec<&mut [f64]> = data.chunks_mut(matrix.cols.max(1)).collect();
+    eliminate(&mut rows, &mut rhs)?;
+    Ok(rhs)
+}
+
+// solve_linear for systems of a fixed size in any Scalar
+pub fn solve_array<const N: usize, S: Scalar>(mut matrix: [[S; N]; N], mut rhs: [S; N]) -> Result<[S; N], usize> {
+    eliminate(&mut matrix, &mut rhs)?;
+    Ok(rhs)
+}
+
+// Overwrites rhs with the solution, swapping and eliminating the rows in place
+fn eliminate<S: Scalar, R: AsRef<[S]> + AsMut<[S]>>(rows: &mut [R], rhs: &mut [S]) -> Result<(), usize> {
+    let n = rhs.len();
+    let mut scale = S::from_f64(0.);
+    for row in rows.iter() {
+        for &a in row.as_ref() {
+            if a.abs() > scale {
+                scale = a.abs();
+            }
+        }
+    }
+    let tolerance = S::from_f64(PIVOT_TOLERANCE) * scale;
+    for column in 0..n {
+        let pivot = (column..n)
+            .max_by(|&i, &j| {
+                let (a, b) = (rows[i].as_ref()[column].abs(), rows[j].as_ref()[column].abs());
+                a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+            })
+            .expect("Column range is not empty");
+        let value = rows[pivot].as_ref()[column];
+        if value.is_nan() || value.abs() <= tolerance {
+            return Err(column);
+        }
+        rows.swap(column, pivot);
+        rhs.swap(column, pivot);
+        let (upper, lower) = rows.split_at_mut(column + 1);
+        let pivot_row = upper[column].as_ref();
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let row = row.as_mut();
+            let factor = row[column] / pivot_row[column];
+            for (entry, &value) in row.iter_mut().zip(pivot_row).skip(column) {
+                *entry = *entry - factor * value;
+            }
+            let value = rhs[column];
+            rhs[column + 1 + offset] = rhs[column + 1 + offset] - factor * value;
+        }
+    }
+    for row in (0..n).rev() {
+        let entries = rows[row].as_ref();
+        let mut value = rhs[row];
+        for (&entry, &solution) in entries.iter().zip(rhs.iter()).skip(row + 1) {
+            value = value - entry * solution;
+        }
+        rhs[row] = value / entries[row];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eigenvalues() {
+        // Companion matrix of (x - 1)(x + 2)(x^2 - 2x + 5) = x^4 - x^3 + x^2 + 9x - 10
+        let coefficients = [-10., 9., 1., -1.];
+        let mut companion = Matrix::zeros(4, 4);
+        for i in 0..4 {
+            if i > 0 {
+                companion[(i, i - 1)] = 1.;
+            }
+            companion[(i, 3)] = -coefficients[i];
+        }
+        let values = eigenvalues(&companion).unwrap();
+        assert_eq!(values.len(), 4);
+        for (re, im) in [(-2., 0.), (1., 0.), (1., 2.), (1., -2.)] {
+            assert!(
+                values.iter().any(|&(x, y)| (x - re).abs() < 1E-10 && (y - im).abs() < 1E-10),
+                "{} + {}i not among {:?}",
+                re,
+                im,
+                values
+            );
+        }
+    }
+    #[test]
+    fn test_solve() {
+        // Needs a row swap for the vanishing first pivot, solution (1, -2, 3)
+        let rows = [[0., 2., 1.], [1., 1., 1.], [2., -1., 3.]];
+        let rhs = [-1., 2., 13.];
+        let mut matrix = Matrix::zeros(3, 3);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                matrix[(i, j)] = value;
+            }
+        }
+        let dynamic = solve_linear(matrix, rhs.to_vec()).unwrap();
+        let fixed = solve_array(rows, rhs).unwrap();
+        for solution in [dynamic.as_slice(), fixed.as_slice()] {
+            for (x, e) in solution.iter().zip([1., -2., 3.]) {
+                assert!((x - e).abs() < 1E-12, "{:?}", solution);
+            }
+        }
+        assert_eq!(solve_array([[1., 2.], [2., 4.]], [1., 2.]), Err(1));
+    }
+}
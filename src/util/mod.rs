@@ -1,5 +1,13 @@
 pub mod constants;
+pub mod double_double;
+pub mod dual;
+pub mod effective_potential;
+pub mod interval;
+pub mod lie_algebra;
+pub mod linear_algebra;
 mod polysolver;
+pub mod random;
+pub mod scalar;
 pub mod stability;
 pub mod perturbativity;
 pub mod image;
\ No newline at end of file
@@ -1,12 +1,196 @@
-use crate::model::BetaFunctionValue;
-use crate::util::constants::{PI_4_2, PI_4_4};
-
-pub fn check_perturbativity<const N: usize>(funs: [BetaFunctionValue; N], threshold: f64) -> bool {
-    let mut sum_b1 = 0.;
-    let mut sum_b2 = 0.;
-    for i in 0..N {
-        sum_b1 += funs[i].b1.abs();
-        sum_b2 += funs[i].b2.abs();
-    }
-    sum_b2 / PI_4_4 < threshold * sum_b1 / PI_4_2
-}
\ No newline at end of file
+use crate::model::LoopTerms;
+use crate::util::scalar::Scalar;
+use std::f64::consts::PI;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct PerturbativityViolation {
+    pub criterion: &'static str,
+    // Index of the offending coupling, if the criterion is evaluated per coupling
+    pub coupling: Option<usize>,
+}
+
+// A condition on the couplings and their beta functions that has to hold at every step for the
+// perturbative running to be trusted. loop_terms are those of the beta function of every coupling
+// and sums their absolute values summed over the couplings, both from the pass that sums the
+// derivative of the step. step_size is the signed change in log scale of a step.
+pub trait PerturbativityCriterion<S: Scalar = f64>: Debug + Send + Sync {
+    fn check(
+        &self,
+        couplings: &[S],
+        loop_terms: &[LoopTerms<S>],
+        sums: &LoopTerms<S>,
+        step_size: f64,
+    ) -> Option<PerturbativityViolation>;
+}
+
+// Two-loop contributions summed over all couplings compared to the summed one-loop contributions
+#[derive(Debug, Clone)]
+pub struct TotalLoopRatio {
+    pub threshold: f64,
+}
+impl<S: Scalar> PerturbativityCriterion<S> for TotalLoopRatio {
+    fn check(
+        &self,
+        _couplings: &[S],
+        _loop_terms: &[LoopTerms<S>],
+        sums: &LoopTerms<S>,
+        _step_size: f64,
+    ) -> Option<PerturbativityViolation> {
+        if sums.two < S::from_f64(self.threshold) * sums.one {
+            None
+        } else {
+            Some(PerturbativityViolation {
+                criterion: "total two-loop/one-loop ratio",
+                coupling: None,
+            })
+        }
+    }
+}
+
+// Contribution at a loop order compared to the one at the order below, separately for every
+// coupling, with the threshold of the ratio
+#[derive(Debug, Clone, Copy)]
+pub enum LoopRatio {
+    TwoLoop(f64),
+    ThreeLoop(f64),
+}
+impl<S: Scalar> PerturbativityCriterion<S> for LoopRatio {
+    fn check(
+        &self,
+        _couplings: &[S],
+        loop_terms: &[LoopTerms<S>],
+        _sums: &LoopTerms<S>,
+        _step_size: f64,
+    ) -> Option<PerturbativityViolation> {
+        let (criterion, threshold) = match *self {
+            LoopRatio::TwoLoop(threshold) => ("two-loop/one-loop ratio", threshold),
+            LoopRatio::ThreeLoop(threshold) => ("three-loop/two-loop ratio", threshold),
+        };
+        for (i, terms) in loop_terms.iter().enumerate() {
+            let (higher, lower) = match self {
+                LoopRatio::TwoLoop(_) => (terms.two, terms.one),
+                LoopRatio::ThreeLoop(_) => (terms.three, terms.two),
+            };
+            // Strict, so that couplings without running at either order pass
+            if higher.abs() > S::from_f64(threshold) * lower.abs() {
+                return Some(PerturbativityViolation {
+                    criterion,
+                    coupling: Some(i),
+                });
+            }
+        }
+        None
+    }
+}
+
+// |coupling_i| < bounds[i]
+#[derive(Debug, Clone)]
+pub struct AbsoluteBound {
+    pub bounds: Vec<f64>,
+}
+impl AbsoluteBound {
+    // g < sqrt(4 pi) for the first num_gauge couplings and |lambda| < 4 pi for the rest
+    pub fn standard(num_gauge: usize, num_couplings: usize) -> Self {
+        let bounds = (0..num_couplings)
+            .map(|i| if i < num_gauge { (4. * PI).sqrt() } else { 4. * PI })
+            .collect();
+        Self { bounds }
+    }
+}
+impl<S: Scalar> PerturbativityCriterion<S> for AbsoluteBound {
+    fn check(
+        &self,
+        couplings: &[S],
+        _loop_terms: &[LoopTerms<S>],
+        _sums: &LoopTerms<S>,
+        _step_size: f64,
+    ) -> Option<PerturbativityViolation> {
+        for (i, (&coupling, &bound)) in couplings.iter().zip(&self.bounds).enumerate() {
+            if coupling.abs() >= S::from_f64(bound) {
+                return Some(PerturbativityViolation {
+                    criterion: "absolute bound",
+                    coupling: Some(i),
+                });
+            }
+        }
+        None
+    }
+}
+
+// Estimated distance in log scale to a Landau pole in the running direction, |lambda / beta|,
+// which is exact for a purely quadratic one-loop beta function
+#[derive(Debug, Clone)]
+pub struct LandauPole {
+    pub min_distance: f64,
+}
+impl<S: Scalar> PerturbativityCriterion<S> for LandauPole {
+    fn check(
+        &self,
+        couplings: &[S],
+        loop_terms: &[LoopTerms<S>],
+        _sums: &LoopTerms<S>,
+        step_size: f64,
+    ) -> Option<PerturbativityViolation> {
+        let zero = S::from_f64(0.);
+        for (i, (&coupling, terms)) in couplings.iter().zip(loop_terms).enumerate() {
+            let beta = terms.total();
+            let growing = coupling * beta * S::from_f64(step_size) > zero;
+            if growing && coupling.abs() < S::from_f64(self.min_distance) * beta.abs() {
+                return Some(PerturbativityViolation {
+                    criterion: "Landau pole proximity",
+                    coupling: Some(i),
+                });
+            }
+        }
+        None
+    }
+}
+
+// Criterion of a run, matched instead of called through a vtable in every step. Criteria defined
+// outside this module go into Custom.
+#[derive(Debug, Clone)]
+pub enum Perturbativity<S: Scalar = f64> {
+    TotalLoopRatio(TotalLoopRatio),
+    LoopRatio(LoopRatio),
+    AbsoluteBound(AbsoluteBound),
+    LandauPole(LandauPole),
+    // Violated as soon as any of the contained criteria is violated
+    AnyOf(Vec<Perturbativity<S>>),
+    Custom(Arc<dyn PerturbativityCriterion<S>>),
+}
+impl<S: Scalar> Perturbativity<S> {
+    // Whether the criterion reads the three-loop terms, which custom criteria are assumed to
+    pub fn reads_three_loops(&self) -> bool {
+        match self {
+            Perturbativity::TotalLoopRatio(_)
+            | Perturbativity::LoopRatio(LoopRatio::TwoLoop(_))
+            | Perturbativity::AbsoluteBound(_) => false,
+            Perturbativity::LoopRatio(LoopRatio::ThreeLoop(_))
+            | Perturbativity::LandauPole(_)
+            | Perturbativity::Custom(_) => true,
+            Perturbativity::AnyOf(criteria) => criteria.iter().any(Perturbativity::reads_three_loops),
+        }
+    }
+}
+impl<S: Scalar> PerturbativityCriterion<S> for Perturbativity<S> {
+    fn check(
+        &self,
+        couplings: &[S],
+        loop_terms: &[LoopTerms<S>],
+        sums: &LoopTerms<S>,
+        step_size: f64,
+    ) -> Option<PerturbativityViolation> {
+        match self {
+            Perturbativity::TotalLoopRatio(criterion) => criterion.check(couplings, loop_terms, sums, step_size),
+            Perturbativity::LoopRatio(criterion) => criterion.check(couplings, loop_terms, sums, step_size),
+            Perturbativity::AbsoluteBound(criterion) => criterion.check(couplings, loop_terms, sums, step_size),
+            Perturbativity::LandauPole(criterion) => criterion.check(couplings, loop_terms, sums, step_size),
+            Perturbativity::AnyOf(criteria) => criteria
+                .iter()
+                .find_map(|criterion| criterion.check(couplings, loop_terms, sums, step_size)),
+            Perturbativity::Custom(criterion) => criterion.check(couplings, loop_terms, sums, step_size),
+        }
+    }
+}
@@ -1,27 +1,82 @@
+use crate::util::interval::{Interval, IntervalBound};
+use crate::util::scalar::Scalar;
+
 const EPSILON: f64 = 1e-15;
 
-pub fn solve_linear(a0: f64, a1: f64) -> f64 {
-    if a1.abs() < EPSILON {
-        return f64::NAN;
+// Newton iterations used to refine f64 roots when solving in a more precise scalar type
+const NEWTON_ITERATIONS: usize = 3;
+
+// Leading coefficients below EPSILON (in units of the f64 machine epsilon) are treated as zero
+fn is_negligible<S: Scalar>(x: S) -> bool {
+    x.abs() < S::from_f64(EPSILON * (S::EPSILON / f64::EPSILON))
+}
+
+pub fn solve_linear<S: Scalar>(a0: S, a1: S) -> S {
+    if is_negligible(a1) {
+        return S::from_f64(f64::NAN);
     }
     -a0 / a1
 }
 
-pub fn solve_quadratic(a0: f64, a1: f64, a2: f64) -> (f64, f64) {
-    if a2.abs() < EPSILON {
-        return (solve_linear(a0, a1), f64::NAN);
+pub fn solve_quadratic<S: Scalar>(a0: S, a1: S, a2: S) -> (S, S) {
+    let nan = S::from_f64(f64::NAN);
+    if is_negligible(a2) {
+        return (solve_linear(a0, a1), nan);
     }
 
-    let d = a1 * a1 - 4.0 * a2 * a0;
-    if d < 0.0 {
-        return (f64::NAN, f64::NAN);
+    let d = a1 * a1 - S::from_f64(4.0) * a2 * a0;
+    if d < S::from_f64(0.0) {
+        return (nan, nan);
     }
     let sqrt_d = d.sqrt();
-    let x1 = (-a1 - sqrt_d) / (2.0 * a2);
-    let x2 = (-a1 + sqrt_d) / (2.0 * a2);
+    let x1 = (-a1 - sqrt_d) / (S::from_f64(2.0) * a2);
+    let x2 = (-a1 + sqrt_d) / (S::from_f64(2.0) * a2);
     (x1, x2)
 }
 
+// The closed-form quartic solution needs transcendental functions, so it is always evaluated
+// in f64 and the roots are then refined in the precision of S
+pub fn solve_quartic<S: Scalar>(a0: S, a1: S, a2: S, a3: S, a4: S) -> (S, S, S, S) {
+    let (x1, x2, x3, x4) = solve_quartic_f64(
+        a0.to_f64(),
+        a1.to_f64(),
+        a2.to_f64(),
+        a3.to_f64(),
+        a4.to_f64(),
+    );
+    let coeffs = [a0, a1, a2, a3, a4];
+    (
+        polish(&coeffs, x1),
+        polish(&coeffs, x2),
+        polish(&coeffs, x3),
+        polish(&coeffs, x4),
+    )
+}
+
+fn polish<S: Scalar>(coeffs: &[S], root: f64) -> S {
+    let mut x = S::from_f64(root);
+    if S::EPSILON >= f64::EPSILON || root.is_nan() {
+        return x;
+    }
+
+    let mut residual = S::from_f64(f64::INFINITY);
+    for _ in 0..NEWTON_ITERATIONS {
+        let mut value = coeffs[coeffs.len() - 1];
+        let mut derivative = S::from_f64(0.);
+        for &coeff in coeffs.iter().rev().skip(1) {
+            derivative = derivative * x + value;
+            value = value * x + coeff;
+        }
+        // Stop once Newton no longer improves, e.g. close to a repeated root
+        if value.is_nan() || value.abs() >= residual || derivative == S::from_f64(0.) {
+            break;
+        }
+        residual = value.abs();
+        x = x - value / derivative;
+    }
+    x
+}
+
 pub fn solve_cubic(a0: f64, a1: f64, a2: f64, a3: f64) -> (f64, f64, f64) {
     if a3.abs() < EPSILON {
         let (x1, x2) = solve_quadratic(a0, a1, a2);
@@ -58,7 +113,7 @@ pub fn solve_cubic(a0: f64, a1: f64, a2: f64, a3: f64) -> (f64, f64, f64) {
     }
 }
 
-pub fn solve_quartic(a0: f64, a1: f64, a2: f64, a3: f64, a4: f64) -> (f64, f64, f64, f64) {
+fn solve_quartic_f64(a0: f64, a1: f64, a2: f64, a3: f64, a4: f64) -> (f64, f64, f64, f64) {
     if a4.abs() < EPSILON {
         let (x1, x2, x3) = solve_cubic(a0, a1, a2, a3);
         return (x1, x2, x3, f64::NAN);
@@ -132,6 +187,15 @@ pub fn solve_quartic(a0: f64, a1: f64, a2: f64, a3: f64, a4: f64) -> (f64, f64,
     }
 }
 
+// Enclosure of the polynomial sum_i coeffs[i] x^i over the whole interval x
+pub fn eval_interval<B: IntervalBound>(coeffs: &[Interval<B>], x: Interval<B>) -> Interval<B> {
+    let mut value = coeffs[coeffs.len() - 1];
+    for &coeff in coeffs.iter().rev().skip(1) {
+        value = value * x + coeff;
+    }
+    value
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,8 @@
+use std::f64::consts::PI;
+
+// Normally distributed number with mean 0 and variance 1 (Box-Muller)
+pub fn standard_normal() -> f64 {
+    let u = 1. - rand::random::<f64>();
+    let v = rand::random::<f64>();
+    (-2. * u.ln()).sqrt() * (2. * PI * v).cos()
+}
@@ -0,0 +1,112 @@
+use crate::util::constants::PI_4_2;
+use crate::util::double_double::DoubleDouble;
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+// Real number type the models and the integrator can be evaluated with. f64 is the default
+// everywhere; DoubleDouble can be used to validate individual points at ~32 significant digits.
+pub trait Scalar:
+    Copy
+    + Debug
+    + PartialOrd
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + Div<f64, Output = Self>
+{
+    // Machine epsilon of the type
+    const EPSILON: f64;
+
+    fn from_f64(x: f64) -> Self;
+    fn to_f64(self) -> f64;
+    // Exact conversion, used to build rigorous interval enclosures
+    fn to_double_double(self) -> DoubleDouble;
+
+    // (4 pi)^2, the factor accompanying every loop order
+    fn loop_factor() -> Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn is_nan(self) -> bool;
+    fn is_finite(self) -> bool;
+
+    // Rational coefficient evaluated at the precision of the type
+    fn rational(numerator: f64, denominator: f64) -> Self {
+        Self::from_f64(numerator) / Self::from_f64(denominator)
+    }
+}
+
+impl Scalar for f64 {
+    const EPSILON: f64 = f64::EPSILON;
+
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+    fn to_f64(self) -> f64 {
+        self
+    }
+    fn to_double_double(self) -> DoubleDouble {
+        DoubleDouble::from_f64(self)
+    }
+
+    fn loop_factor() -> Self {
+        PI_4_2
+    }
+
+    fn abs(self) -> Self {
+        f64::abs(self)
+    }
+    fn sqrt(self) -> Self {
+        f64::sqrt(self)
+    }
+    fn powi(self, n: i32) -> Self {
+        f64::powi(self, n)
+    }
+    fn is_nan(self) -> bool {
+        f64::is_nan(self)
+    }
+    fn is_finite(self) -> bool {
+        f64::is_finite(self)
+    }
+}
+
+impl Scalar for DoubleDouble {
+    const EPSILON: f64 = 1.0 / (1u128 << 104) as f64;
+
+    fn from_f64(x: f64) -> Self {
+        DoubleDouble::from_f64(x)
+    }
+    fn to_f64(self) -> f64 {
+        DoubleDouble::to_f64(self)
+    }
+    fn to_double_double(self) -> DoubleDouble {
+        self
+    }
+
+    fn loop_factor() -> Self {
+        DoubleDouble::from_f64(16.) * DoubleDouble::PI * DoubleDouble::PI
+    }
+
+    fn abs(self) -> Self {
+        DoubleDouble::abs(self)
+    }
+    fn sqrt(self) -> Self {
+        DoubleDouble::sqrt(self)
+    }
+    fn powi(self, n: i32) -> Self {
+        DoubleDouble::powi(self, n)
+    }
+    fn is_nan(self) -> bool {
+        DoubleDouble::is_nan(self)
+    }
+    fn is_finite(self) -> bool {
+        self.hi.is_finite() && self.lo.is_finite()
+    }
+}
@@ -1,13 +1,15 @@
-use crate::util::polysolver::{solve_quadratic, solve_quartic};
+use crate::util::interval::{Interval, IntervalBound};
+use crate::util::polysolver::{eval_interval, solve_quadratic, solve_quartic};
+use crate::util::scalar::Scalar;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum FinalStabilityResult {
     Stable,
     UnstableAllowed(StabilityResult),
     UnstableDisallowed(StabilityResult)
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum StabilityResult {
     Stable,
     Violated1([f64; 3]),
@@ -15,54 +17,93 @@ pub enum StabilityResult {
     ViolatedReqInit,
 }
 
-pub fn stab2vev(a0: f64, a1: f64, a2: f64) -> StabilityResult {
-    if a0 <= 0. {
+// One of the conditions a model checks for stability, classified by whether its violation leads
+// to an allowed breaking pattern
+#[derive(Debug, Clone, Copy)]
+pub struct StabilityCondition {
+    pub name: &'static str,
+    pub allowed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StabilityViolation {
+    // Position of the condition in Model::stability_conditions
+    pub index: usize,
+    pub condition: StabilityCondition,
+    pub result: StabilityResult,
+}
+impl StabilityViolation {
+    pub fn final_result(&self) -> FinalStabilityResult {
+        if self.condition.allowed {
+            FinalStabilityResult::UnstableAllowed(self.result.clone())
+        } else {
+            FinalStabilityResult::UnstableDisallowed(self.result.clone())
+        }
+    }
+}
+
+// Outcome of an interval evaluation, which may be unable to resolve a decision
+#[derive(Debug)]
+pub enum Certified<T> {
+    Decided(T),
+    Undecided,
+}
+
+// Upper limit on the number of boxes examined by the branch-and-bound in stab3vev_certified
+const MAX_BOXES: usize = 1 << 14;
+
+pub fn stab2vev<S: Scalar>(a0: S, a1: S, a2: S) -> StabilityResult {
+    let (zero, two) = (S::from_f64(0.), S::from_f64(2.));
+    if a0 <= zero {
         StabilityResult::Violated1([1., 0., 0.])
-    } else if a2 <= 0. {
+    } else if a2 <= zero {
         StabilityResult::Violated1([0., 1., 0.])
-    } else if a1 + 2. * (a0*a2).sqrt() <= 0. {
+    } else if a1 + two * (a0*a2).sqrt() <= zero {
+        let (a0, a2) = (a0.to_f64(), a2.to_f64());
         StabilityResult::Violated2([a2.powf(0.25), a0.powf(0.25), 0.0], [a2.powf(0.25), -a0.powf(0.25), 0.0])
     } else {
         StabilityResult::Stable
     }
 }
 
-pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64) -> StabilityResult {
-    if alpha <= 0. {
+pub fn stab3vev<S: Scalar>(alpha: S, b0: S, b1: S, b2: S, c0: S, c1: S, c2: S) -> StabilityResult {
+    let (zero, two, four) = (S::from_f64(0.), S::from_f64(2.), S::from_f64(4.));
+    if alpha <= zero {
         return StabilityResult::Violated1([1., 0., 0.]);
     }
-    if c0 <= 0. {
+    if c0 <= zero {
         return StabilityResult::Violated1([0., 1., 0.]);
     }
-    if c2 <= 0. {
+    if c2 <= zero {
         return StabilityResult::Violated1([0., 0., 1.]);
     }
-    if c1 + 2. * (c0*c2).sqrt() <= 0. {
+    if c1 + two * (c0*c2).sqrt() <= zero {
+        let (c0, c2) = (c0.to_f64(), c2.to_f64());
         return StabilityResult::Violated2(
             [0., c2.powf(0.25), c0.powf(0.25)],
             [0., c2.powf(0.25), -c0.powf(0.25)]
         );
     }
 
-    let a0 = -b0 * b0 + 4. * alpha * c0;
-    let a1 = -2. * b0 * b1;
-    let a2 = -b1 * b1 - 2. * b0 * b2 + 4. * alpha * c1;
-    let a3 = -2. * b1 * b2;
-    let a4 = -b2 * b2 + 4. * alpha * c2;
+    let a0 = -b0 * b0 + four * alpha * c0;
+    let a1 = -two * b0 * b1;
+    let a2 = -b1 * b1 - two * b0 * b2 + four * alpha * c1;
+    let a3 = -two * b1 * b2;
+    let a4 = -b2 * b2 + four * alpha * c2;
 
     let (d1, d2, d3, d4) = solve_quartic(a0, a1, a2, a3, a4);
     let (beta1, beta2) = solve_quadratic(b0, b1, b2);
 
-    if d1.is_nan() && !(beta1.is_nan() && b2 <= 0.) {
+    if d1.is_nan() && !(beta1.is_nan() && b2 <= zero) {
         return StabilityResult::Stable;
     } else if beta1.is_nan() { // No real roots
-        if b2 > 0. {
+        if b2 > zero {
             return StabilityResult::Stable;
         }
     } else {
         if d3.is_nan() {
-            if b2 > 0. {
-                if a4 > 0. {
+            if b2 > zero {
+                if a4 > zero {
                     if beta2 < d1 || beta1 > d2 {
                         return StabilityResult::Stable;
                     }
@@ -72,15 +113,15 @@ pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64
                     }
                 }
             } else {
-                if a4 > 0. {
+                if a4 > zero {
                     if beta2 < d1 && beta1 > d2 {
                         return StabilityResult::Stable;
                     }
                 }
             }
         } else {
-            if b2 > 0. {
-                if a4 > 0. {
+            if b2 > zero {
+                if a4 > zero {
                     if beta2 < d1 || beta1 > d4 || (beta1 > d2 && beta2 < d3) {
                         return StabilityResult::Stable;
                     }
@@ -90,7 +131,7 @@ pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64
                     }
                 }
             } else {
-                if a4 > 0. {
+                if a4 > zero {
                     if beta1 < d1 || beta2 > d4 {
                         return StabilityResult::Stable;
                     }
@@ -99,14 +140,16 @@ pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64
         }
     }
 
-    if a0 <= 0. {
+    let (alpha, c0, c1, c2) = (alpha.to_f64(), c0.to_f64(), c1.to_f64(), c2.to_f64());
+
+    if a0 <= zero {
         return StabilityResult::Violated2(
             [c0.powf(0.25), alpha.powf(0.25), 0.],
             [c0.powf(0.25), -alpha.powf(0.25), 0.]
         )
     }
 
-    if a4 <= 0. {
+    if a4 <= zero {
         return StabilityResult::Violated2(
             [c0.powf(0.25), 0., alpha.powf(0.25)],
             [c0.powf(0.25), 0., -alpha.powf(0.25)]
@@ -120,7 +163,7 @@ pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64
     // Find the two roots closest together
     let mut repeated = 0.;
     let mut min_dist = f64::MAX;
-    let roots = [d1, d2, d3, d4];
+    let roots = [d1, d2, d3, d4].map(S::to_f64);
     for i in 0..3 {
         if roots[i+1].is_nan() {
             break;
@@ -145,3 +188,167 @@ pub fn stab3vev(alpha: f64, b0: f64, b1: f64, b2: f64, c0: f64, c1: f64, c2: f64
     )
 }
 
+pub fn stab2vev_certified<B: IntervalBound>(
+    a0: Interval<B>,
+    a1: Interval<B>,
+    a2: Interval<B>,
+) -> Certified<StabilityResult> {
+    for (value, vev) in [(a0, [1., 0., 0.]), (a2, [0., 1., 0.])] {
+        if value.is_nonpositive() {
+            return Certified::Decided(StabilityResult::Violated1(vev));
+        }
+        if !value.is_positive() {
+            return Certified::Undecided;
+        }
+    }
+
+    let cross = a1 + 2. * (a0 * a2).sqrt();
+    if cross.is_positive() {
+        Certified::Decided(StabilityResult::Stable)
+    } else if cross.is_nonpositive() {
+        let (a0, a2) = (a0.mid().to_f64(), a2.mid().to_f64());
+        Certified::Decided(StabilityResult::Violated2(
+            [a2.powf(0.25), a0.powf(0.25), 0.0],
+            [a2.powf(0.25), -a0.powf(0.25), 0.0],
+        ))
+    } else {
+        Certified::Undecided
+    }
+}
+
+// Interval version of stab3vev. The potential along (x, 1, r) is alpha x^4 + B(r) x^2 + C(r)
+// with B(r) = b0 + b1 r + b2 r^2 and C(r) = c0 + c1 r^2 + c2 r^4, which is positive for all x
+// iff C(r) > 0 and either B(r) > 0 or D(r) = 4 alpha C(r) - B(r)^2 > 0. Instead of locating
+// the roots of D this is checked by branch-and-bound over r in [-1, 1], together with the
+// chart (x, r, 1) that covers the remaining directions.
+pub fn stab3vev_certified<B: IntervalBound>(
+    alpha: Interval<B>,
+    b0: Interval<B>,
+    b1: Interval<B>,
+    b2: Interval<B>,
+    c0: Interval<B>,
+    c1: Interval<B>,
+    c2: Interval<B>,
+) -> Certified<StabilityResult> {
+    for (value, vev) in [(alpha, [1., 0., 0.]), (c0, [0., 1., 0.]), (c2, [0., 0., 1.])] {
+        if value.is_nonpositive() {
+            return Certified::Decided(StabilityResult::Violated1(vev));
+        }
+        if !value.is_positive() {
+            return Certified::Undecided;
+        }
+    }
+
+    let zero = Interval::point(0.);
+    let four_alpha = 4. * alpha;
+    let charts = [
+        ([b0, b1, b2], [c0, zero, c1, zero, c2]),
+        ([b2, b1, b0], [c2, zero, c1, zero, c0]),
+    ];
+    let positive = |b: Interval<B>, c: Interval<B>| {
+        c.is_positive() && (b.is_positive() || (four_alpha * c - b.square()).is_positive())
+    };
+    let violated = |b: Interval<B>, c: Interval<B>| {
+        c.is_nonpositive() || (b.is_nonpositive() && (four_alpha * c - b.square()).is_nonpositive())
+    };
+
+    let mut undecided = false;
+    let mut boxes = 0;
+    for (chart, (b, c)) in charts.iter().enumerate() {
+        let mut stack = vec![(Interval::new(B::from_f64(-1.), B::from_f64(1.)), 0)];
+        while let Some((r, depth)) = stack.pop() {
+            boxes += 1;
+            if positive(eval_interval(b, r), eval_interval(c, r)) {
+                continue;
+            }
+
+            let mid = r.mid();
+            let point = Interval::new(mid, mid);
+            let (b_mid, c_mid) = (eval_interval(b, point), eval_interval(c, point));
+            if violated(b_mid, c_mid) {
+                return Certified::Decided(certified_violation(
+                    [alpha, b0, b1, b2, c0, c1, c2],
+                    chart,
+                    mid.to_f64(),
+                    b_mid.mid().to_f64(),
+                ));
+            }
+
+            if depth >= B::BISECTION_DEPTH || boxes >= MAX_BOXES {
+                undecided = true;
+                continue;
+            }
+            let (left, right) = r.bisect();
+            stack.push((left, depth + 1));
+            stack.push((right, depth + 1));
+        }
+    }
+
+    if undecided {
+        Certified::Undecided
+    } else {
+        Certified::Decided(StabilityResult::Stable)
+    }
+}
+
+// Flat direction reported for a certified violation. Whenever the f64 evaluation agrees that
+// the condition is violated its direction is used, so that both paths report the same VEVs.
+fn certified_violation<B: IntervalBound>(
+    coefficients: [Interval<B>; 7],
+    chart: usize,
+    r: f64,
+    b: f64,
+) -> StabilityResult {
+    let [alpha, b0, b1, b2, c0, c1, c2] = coefficients.map(|c| c.mid().to_f64());
+    match stab3vev(alpha, b0, b1, b2, c0, c1, c2) {
+        StabilityResult::Stable => {}
+        other => return other,
+    }
+
+    let x = (-b / (2. * alpha)).max(0.).sqrt();
+    if chart == 0 {
+        StabilityResult::Violated2([x, 1., r], [-x, 1., r])
+    } else {
+        StabilityResult::Violated2([x, r, 1.], [-x, r, 1.])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::double_double::DoubleDouble;
+
+    fn certify<B: IntervalBound>(coefficients: [f64; 7]) -> Certified<StabilityResult> {
+        let [alpha, b0, b1, b2, c0, c1, c2] = coefficients.map(Interval::<B>::point);
+        stab3vev_certified(alpha, b0, b1, b2, c0, c1, c2)
+    }
+
+    #[test]
+    fn test_certified_agrees_with_fast() {
+        let cases = [
+            [1.2, 3.3, -1.2, 1.4, 3.1, 4.2, 1.4],
+            [1.2, -3.3, 1.2, 1.4, 3.1, 4.2, 1.4],
+            [0.4, -1.0, 0.5, -0.8, 1.0, 0.2, 0.6],
+            [0.4, 2.0, 3.0, 1.0, 1.0, -2.5, 1.0],
+        ];
+        for coefficients in cases {
+            let [alpha, b0, b1, b2, c0, c1, c2] = coefficients;
+            let fast = matches!(stab3vev(alpha, b0, b1, b2, c0, c1, c2), StabilityResult::Stable);
+            match certify::<f64>(coefficients) {
+                Certified::Decided(result) => {
+                    assert_eq!(fast, matches!(result, StabilityResult::Stable), "{:?}", coefficients)
+                }
+                Certified::Undecided => panic!("Undecided for {:?}", coefficients),
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundary_is_undecided() {
+        // B(r) = -2 (1 + r^2) < 0 and D(r) = 4 (1 + r^2)^2 - B(r)^2 vanishes identically, so the
+        // potential is exactly flat along every direction and no finite precision can decide
+        let coefficients = [1., -2., 0., -2., 1., 2., 1.];
+        assert!(matches!(certify::<f64>(coefficients), Certified::Undecided));
+        assert!(matches!(certify::<DoubleDouble>(coefficients), Certified::Undecided));
+    }
+}